@@ -1,7 +1,13 @@
 use web_sys::HtmlTextAreaElement;
 use yew::prelude::*;
 
-use yew_flow::{store::WorkspaceStore, workspace::YewFlowValues, Workspace};
+use yew_flow::{
+    components::node::models::{FieldKind, NodeField, NodeTemplate},
+    graph::HighlightMode,
+    store::WorkspaceStore,
+    workspace::YewFlowValues,
+    NodePalette, Workspace,
+};
 
 use crate::utils::flow_utils::{parse_flow_json_text_to_values, values_to_flow_json_text};
 
@@ -11,14 +17,94 @@ mod utils;
 fn app() -> Html {
     let prevent_changes = use_state(|| false);
     let values = use_state(|| {
-        let WorkspaceStore { nodes, edges, .. } = WorkspaceStore::generate();
-        YewFlowValues { nodes, edges }
+        let WorkspaceStore {
+            nodes,
+            edges,
+            groups,
+            comments,
+            ..
+        } = WorkspaceStore::generate();
+        YewFlowValues {
+            nodes,
+            edges,
+            groups,
+            comments,
+        }
     });
     let error = use_state(|| None);
+    let templates = use_state(|| {
+        vec![
+            ("Source", "#00aa55", 0, 1),
+            ("Transform", "#ff0000", 1, 1),
+            ("Merge", "#aa00ff", 2, 1),
+            ("Sink", "#0088ff", 1, 0),
+        ]
+        .into_iter()
+        .map(|(title, color, inputs, outputs)| NodeTemplate {
+            kind: None,
+            title: title.to_string(),
+            color: color.to_string(),
+            inputs,
+            outputs,
+            fields: Vec::new(),
+        })
+        .chain(std::iter::once(NodeTemplate {
+            kind: None,
+            title: "Filter".to_string(),
+            color: "#ffaa00".to_string(),
+            inputs: 1,
+            outputs: 1,
+            fields: vec![
+                NodeField {
+                    key: "name".to_string(),
+                    label: "Name".to_string(),
+                    kind: FieldKind::Text,
+                },
+                NodeField {
+                    key: "threshold".to_string(),
+                    label: "Threshold".to_string(),
+                    kind: FieldKind::Number {
+                        min: Some(0.),
+                        max: Some(1.),
+                        step: Some(0.1),
+                    },
+                },
+                NodeField {
+                    key: "mode".to_string(),
+                    label: "Mode".to_string(),
+                    kind: FieldKind::Select {
+                        options: vec!["keep".to_string(), "drop".to_string()],
+                    },
+                },
+                NodeField {
+                    key: "enabled".to_string(),
+                    label: "Enabled".to_string(),
+                    kind: FieldKind::Checkbox,
+                },
+                NodeField {
+                    key: "tint".to_string(),
+                    label: "Tint".to_string(),
+                    kind: FieldKind::Color,
+                },
+            ],
+        }))
+        .collect::<Vec<_>>()
+    });
     let text_area_ref = use_node_ref();
     let json_text = use_state(|| {
-        let WorkspaceStore { nodes, edges, .. } = WorkspaceStore::generate();
-        let values = YewFlowValues { nodes, edges };
+        let WorkspaceStore {
+            nodes,
+            edges,
+            groups,
+            comments,
+            ..
+        } = WorkspaceStore::generate();
+        let values = YewFlowValues {
+            nodes,
+            edges,
+            groups,
+            comments,
+        };
         values_to_flow_json_text(&values).unwrap()
     });
 
@@ -103,11 +189,16 @@ fn app() -> Html {
                 </h1>
             </div>
             <div class="flex-1 min-h-0 flex">
+                <div class="basis-40 mr-2">
+                    <NodePalette templates={(*templates).clone()} />
+                </div>
                 <div class="flex-1 mr-2 h-full flex flex-col min-h-0">
                     <Workspace
                         values={(*values).clone()}
                         {on_change}
                         prevent_changes={(*prevent_changes).clone()}
+                        node_templates={(*templates).clone()}
+                        highlight_mode={HighlightMode::Both}
                     />
                 </div>
                 <div class="basis-1/3 h-full flex flex-col relative">
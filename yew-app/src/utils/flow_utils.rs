@@ -1,24 +1,34 @@
 use serde::Serialize;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use serde_json::ser::PrettyFormatter;
-use yew_flow::workspace::YewFlowValues;
+use yew_flow::{format::FlowDocument, workspace::YewFlowValues};
 
 /// # Values to flow json text
 /// 
-/// Convert state values to flow json text.
+/// Convert state values to flow json text, wrapped in a versioned document.
 pub fn values_to_flow_json_text(values: &YewFlowValues) -> Result<String> {
     // pretty formatter with ident: ref: https://stackoverflow.com/questions/42722169/generate-pretty-indented-json-with-serde
     let mut ser =
         serde_json::Serializer::with_formatter(Vec::new(), PrettyFormatter::with_indent(b"    "));
-    let json_value = serde_json::to_value(&(*values).clone())?;
+    let json_value = serde_json::to_value(&FlowDocument::new((*values).clone(), None))?;
     json_value.serialize(&mut ser)?;
     Ok(String::from_utf8(ser.into_inner())?)
 }
 
 /// # Parse Flow Json text to Values
 /// 
-/// Parse flow json text into values.
+/// Parse flow json text of any format version into values. Fails on values
+/// with dangling connectors, duplicate ids or half-connected edges.
 pub fn parse_flow_json_text_to_values(json_text: &str) -> Result<YewFlowValues> {
-    let values = serde_json::from_str::<YewFlowValues>(&json_text)?;
+    let values = FlowDocument::from_json_str(json_text)?.flow;
+    let errors = values
+        .validate()
+        .into_iter()
+        .filter(|diagnostic| diagnostic.is_error())
+        .map(|diagnostic| diagnostic.to_string())
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        bail!(errors.join("\n"));
+    }
     Ok(values)
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+//! Print the JSON Schema of the flow document format after checking the
+//! fixtures in `fixtures/` against it. Pass `values` to print the schema of
+//! the bare values instead.
+//!
+//! Run with `cargo run --example schema --features schema > flow.schema.json`.
+
+use std::{env, fs, path::Path};
+
+use jsonschema::JSONSchema;
+use serde_json::Value;
+use yew_flow::format::{document_schema, migrate, values_schema, FORMAT_VERSION};
+
+fn read_fixture(version: u32) -> Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(format!("v{}.json", version));
+    let text = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("missing fixture {}: {}", path.display(), err));
+    serde_json::from_str(&text).unwrap()
+}
+
+fn check(schema: &JSONSchema, value: &Value, name: &str) {
+    if let Err(errors) = schema.validate(value) {
+        for error in errors {
+            eprintln!("{}: {} at {}", name, error, error.instance_path);
+        }
+        panic!("{} does not match the schema", name);
+    }
+}
+
+fn main() {
+    let document = serde_json::to_value(document_schema()).unwrap();
+    let values = serde_json::to_value(values_schema()).unwrap();
+    let document_validator = JSONSchema::compile(&document).expect("invalid document schema");
+    let values_validator = JSONSchema::compile(&values).expect("invalid values schema");
+
+    // older fixtures must match once upgraded, the bare values of `v0`
+    // also match the values schema as they are
+    for version in 0..=FORMAT_VERSION {
+        let fixture = read_fixture(version);
+        if version == 0 {
+            check(&values_validator, &fixture, "v0 values");
+        }
+        let upgraded = migrate(fixture).unwrap();
+        check(&document_validator, &upgraded, &format!("v{}", version));
+        eprintln!("v{}: ok", version);
+    }
+
+    let schema = match env::args().nth(1).as_deref() {
+        Some("values") => values,
+        _ => document,
+    };
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use yew::prelude::*;
+
+use crate::{
+    components::{
+        comment::models::Comment,
+        edge::models::Edge,
+        group::models::Group,
+        node::models::{Node, NodeInput, NodeOutput},
+    },
+    types::{
+        standard_id::{IdentifierExt, StandardId},
+        standard_unit::StandardUnit,
+    },
+    workspace::YewFlowValues,
+};
+
+/// # Copy Subgraph
+///
+/// Get the nodes and comments with `node_ids` together with the edges
+/// connecting the nodes to each other.
+pub fn copy_subgraph(
+    nodes: &[Node],
+    edges: &[Edge],
+    comments: &[Comment],
+    node_ids: &[StandardId],
+) -> YewFlowValues {
+    let nodes = nodes
+        .iter()
+        .filter(|node| node_ids.contains(&node.id))
+        .cloned()
+        .collect::<Vec<_>>();
+    let has_output = |id: &StandardId| {
+        nodes
+            .iter()
+            .any(|node| node.outputs.iter().any(|output| output.id == *id))
+    };
+    let has_input = |id: &StandardId| {
+        nodes
+            .iter()
+            .any(|node| node.inputs.iter().any(|input| input.id == *id))
+    };
+    let edges = edges
+        .iter()
+        .filter(|edge| match (&edge.from_output, &edge.to_input) {
+            (Some(from_output), Some(to_input)) => has_output(from_output) && has_input(to_input),
+            _ => false,
+        })
+        .cloned()
+        .collect();
+    let comments = comments
+        .iter()
+        .filter(|comment| node_ids.contains(&comment.id))
+        .cloned()
+        .collect();
+    YewFlowValues {
+        nodes,
+        edges,
+        groups: Vec::new(),
+        comments,
+    }
+}
+
+/// # Regenerate Ids
+///
+/// Give every node, port, edge, group and comment of `values` a new unique id, remap
+/// the edge endpoints/group children to the new ids and offset everything by
+/// (`dx`, `dy`). Used to insert copies of a subgraph next to the originals.
+/// Subflows are regenerated too, keeping exposed connectors linked to the
+/// inner connectors they forward to.
+pub fn regenerate_ids(values: YewFlowValues, dx: StandardUnit, dy: StandardUnit) -> YewFlowValues {
+    regenerate_ids_with(values, dx, dy, &mut HashMap::new())
+}
+
+/// Get a new id for a port and remember it to remap the edges.
+fn regenerate_port_id(
+    port_ids: &mut HashMap<StandardId, StandardId>,
+    id: StandardId,
+) -> StandardId {
+    let new_id = StandardId::generate();
+    port_ids.insert(id, new_id.clone());
+    new_id
+}
+
+/// `regenerate_ids` with a shared map of old port id -> new port id.
+fn regenerate_ids_with(
+    values: YewFlowValues,
+    dx: StandardUnit,
+    dy: StandardUnit,
+    port_ids: &mut HashMap<StandardId, StandardId>,
+) -> YewFlowValues {
+    // old node id -> new node id
+    let mut node_ids = HashMap::<StandardId, StandardId>::new();
+    let mut nodes = Vec::with_capacity(values.nodes.len());
+    for node in values.nodes {
+        let subflow = node
+            .subflow
+            .map(|inner| Box::new(regenerate_ids_with(*inner, 0., 0., port_ids)));
+        let id = StandardId::generate();
+        node_ids.insert(node.id, id.clone());
+        nodes.push(Node {
+            id,
+            x: node.x + dx,
+            y: node.y + dy,
+            inputs: node
+                .inputs
+                .into_iter()
+                .map(|input| NodeInput {
+                    // the inner connectors are regenerated first
+                    exposes: subflow
+                        .as_ref()
+                        .and_then(|_| port_ids.get(input.exposed_id()).cloned()),
+                    id: regenerate_port_id(port_ids, input.id),
+                    reference: NodeRef::default(),
+                    ..input
+                })
+                .collect(),
+            outputs: node
+                .outputs
+                .into_iter()
+                .map(|output| NodeOutput {
+                    exposes: subflow
+                        .as_ref()
+                        .and_then(|_| port_ids.get(output.exposed_id()).cloned()),
+                    id: regenerate_port_id(port_ids, output.id),
+                    reference: NodeRef::default(),
+                    ..output
+                })
+                .collect(),
+            subflow,
+            ..node
+        });
+    }
+    let edges = values
+        .edges
+        .into_iter()
+        .map(|edge| Edge {
+            id: StandardId::generate(),
+            from_output: edge
+                .from_output
+                .as_ref()
+                .and_then(|id| port_ids.get(id).cloned()),
+            to_input: edge
+                .to_input
+                .as_ref()
+                .and_then(|id| port_ids.get(id).cloned()),
+            x1: edge.x1 + dx,
+            y1: edge.y1 + dy,
+            x2: edge.x2 + dx,
+            y2: edge.y2 + dy,
+            ..edge
+        })
+        .collect();
+    let groups = values
+        .groups
+        .into_iter()
+        .map(|group| Group {
+            id: StandardId::generate(),
+            x: group.x + dx,
+            y: group.y + dy,
+            children: group
+                .children
+                .iter()
+                .filter_map(|id| node_ids.get(id).cloned())
+                .collect(),
+            ..group
+        })
+        .collect();
+    let comments = values
+        .comments
+        .into_iter()
+        .map(|comment| Comment {
+            id: StandardId::generate(),
+            x: comment.x + dx,
+            y: comment.y + dy,
+            ..comment
+        })
+        .collect();
+    YewFlowValues {
+        nodes,
+        edges,
+        groups,
+        comments,
+    }
+}
+
+/// Serialize values to be put on the clipboard.
+pub fn values_to_clipboard_text(values: &YewFlowValues) -> serde_json::Result<String> {
+    serde_json::to_string(values)
+}
+
+/// Parse values from clipboard text.
+pub fn parse_clipboard_text_to_values(text: &str) -> serde_json::Result<YewFlowValues> {
+    serde_json::from_str(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{components::node::models::NodeTemplate, subflow::collapse_to_subflow};
+
+    #[test]
+    fn regenerated_subflow_keeps_exposed_connectors_linked() {
+        let template = NodeTemplate {
+            kind: None,
+            title: "node".to_string(),
+            color: "#000000".to_string(),
+            inputs: 1,
+            outputs: 1,
+            fields: Vec::new(),
+        };
+        let (a, b) = (template.instantiate(0., 0.), template.instantiate(0., 0.));
+        let mut values = YewFlowValues {
+            edges: vec![Edge {
+                from_output: Some(a.outputs[0].id.clone()),
+                to_input: Some(b.inputs[0].id.clone()),
+                ..Default::default()
+            }],
+            nodes: vec![a.clone(), b],
+            groups: Vec::new(),
+            comments: Vec::new(),
+        };
+        collapse_to_subflow(&mut values, &[a.id], "sub".to_string()).unwrap();
+
+        let copy = regenerate_ids(values.clone(), 10., 10.);
+        let subflow = copy
+            .nodes
+            .iter()
+            .find(|node| node.subflow.is_some())
+            .unwrap();
+        let inner_ports = subflow.subflow.as_ref().unwrap().nodes[0].port_ids();
+        let output = &subflow.outputs[0];
+        assert!(inner_ports.contains(output.exposed_id()));
+        assert_ne!(output.exposed_id(), &a.outputs[0].id);
+        assert_eq!(copy.edges[0].from_output.as_ref(), Some(&output.id));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod models;
+pub mod render_comment;
+pub mod render_comment_list;
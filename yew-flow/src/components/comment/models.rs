@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{COMMENT_HEIGHT, COMMENT_WIDTH},
+    types::{
+        standard_id::{IdentifierExt, StandardId},
+        standard_unit::StandardUnit,
+    },
+};
+
+/// # Comment
+///
+/// Sticky note on the canvas. Comments have no connectors
+/// and can never be connected to edges.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Comment {
+    pub id: StandardId,
+    pub x: StandardUnit,
+    pub y: StandardUnit,
+    pub width: StandardUnit,
+    pub height: StandardUnit,
+    /// Hex color of the note, e.g. `#fde68a`.
+    pub color: String,
+    /// Multiline text of the note.
+    pub text: String,
+    /// Render `text` as markdown.
+    #[serde(default)]
+    pub markdown: bool,
+}
+
+impl Comment {
+    /// Create an empty comment with the default size at (`x`, `y`).
+    pub fn new(x: StandardUnit, y: StandardUnit) -> Self {
+        Self {
+            id: StandardId::generate(),
+            x,
+            y,
+            width: COMMENT_WIDTH,
+            height: COMMENT_HEIGHT,
+            color: "#fde68a".to_string(),
+            text: String::new(),
+            markdown: false,
+        }
+    }
+}
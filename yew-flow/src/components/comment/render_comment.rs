@@ -0,0 +1,312 @@
+use colorsys::{Hsl, Rgb};
+use pulldown_cmark::{html::push_html, Event, Parser, Tag};
+use web_sys::{Element, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::utils::capture_pointer;
+
+use super::models::Comment;
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct RenderCommentProps {
+    pub comment: Comment,
+    /// Whether the comment is part of the selection.
+    pub selected: bool,
+    /// Called when the comment is grabbed to be moved.
+    pub on_pointer_down: Callback<(Comment, PointerEvent)>,
+    /// Called when the resize handle is grabbed.
+    pub on_resize_pointer_down: Callback<(Comment, PointerEvent)>,
+    pub on_click: Callback<(Comment, MouseEvent)>,
+    /// Called with the updated comment when its text is edited.
+    pub on_change: Callback<Comment>,
+}
+
+/// Whether a link/image url is safe to render. Only http(s) and mailto
+/// urls, relative urls and fragments are allowed.
+fn is_allowed_url(url: &str) -> bool {
+    // browsers ignore whitespace and control characters in the scheme
+    let url = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_lowercase();
+    match url.find([':', '/', '?', '#']) {
+        Some(i) if url[i..].starts_with(':') => ["http", "https", "mailto"].contains(&&url[..i]),
+        _ => true,
+    }
+}
+
+/// Render markdown to html. Raw html in the text is escaped and
+/// link/image urls with other schemes than http(s) and mailto are blanked.
+fn markdown_to_html(text: &str) -> String {
+    let parser = Parser::new(text).map(|event| match event {
+        Event::Html(html) => Event::Text(html),
+        Event::Start(Tag::Link(link_type, dest, title)) if !is_allowed_url(&dest) => {
+            Event::Start(Tag::Link(link_type, "".into(), title))
+        }
+        Event::Start(Tag::Image(link_type, dest, title)) if !is_allowed_url(&dest) => {
+            Event::Start(Tag::Image(link_type, "".into(), title))
+        }
+        event => event,
+    });
+    let mut html = String::new();
+    push_html(&mut html, parser);
+    html
+}
+
+#[function_component(RenderComment)]
+pub fn render_comment(
+    RenderCommentProps {
+        comment,
+        selected,
+        on_pointer_down,
+        on_resize_pointer_down,
+        on_click,
+        on_change,
+    }: &RenderCommentProps,
+) -> Html {
+    let editing = use_state(|| false);
+    let text_area_ref = use_node_ref();
+    let markdown_ref = use_node_ref();
+
+    {
+        let text_area_ref = text_area_ref.clone();
+        use_effect_with_deps(
+            // focus the editor when editing starts
+            |(editing, text_area_ref)| {
+                if *editing {
+                    if let Some(text_area) = text_area_ref.cast::<HtmlTextAreaElement>() {
+                        let _ = text_area.focus();
+                    }
+                }
+                || ()
+            },
+            (*editing, text_area_ref),
+        );
+    }
+    {
+        let markdown_ref = markdown_ref.clone();
+        use_effect_with_deps(
+            |(text, markdown, editing, markdown_ref)| {
+                if *markdown && !*editing {
+                    if let Some(element) = markdown_ref.cast::<Element>() {
+                        element.set_inner_html(&markdown_to_html(text));
+                    }
+                }
+                || ()
+            },
+            (
+                comment.text.clone(),
+                comment.markdown,
+                *editing,
+                markdown_ref,
+            ),
+        );
+    }
+
+    let handle_pointer_down = {
+        let on_pointer_down = on_pointer_down.clone();
+        let comment = comment.clone();
+        Callback::from(move |e: PointerEvent| {
+            // let secondary pointers bubble up for multi-touch gestures
+            if !e.is_primary() {
+                return;
+            }
+            e.stop_propagation();
+            capture_pointer(&e);
+            on_pointer_down.emit((comment.clone(), e))
+        })
+    };
+    let handle_resize_pointer_down = {
+        let on_resize_pointer_down = on_resize_pointer_down.clone();
+        let comment = comment.clone();
+        Callback::from(move |e: PointerEvent| {
+            if !e.is_primary() {
+                return;
+            }
+            e.stop_propagation();
+            capture_pointer(&e);
+            on_resize_pointer_down.emit((comment.clone(), e))
+        })
+    };
+    let handle_click = {
+        let on_click = on_click.clone();
+        let comment = comment.clone();
+        Callback::from(move |e: MouseEvent| on_click.emit((comment.clone(), e)))
+    };
+    let handle_double_click = {
+        let editing = editing.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            editing.set(true)
+        })
+    };
+    let commit = {
+        let editing = editing.clone();
+        let on_change = on_change.clone();
+        let comment = comment.clone();
+        let text_area_ref = text_area_ref.clone();
+        move || {
+            if let Some(text_area) = text_area_ref.cast::<HtmlTextAreaElement>() {
+                if text_area.value() != comment.text {
+                    on_change.emit(Comment {
+                        text: text_area.value(),
+                        ..comment.clone()
+                    });
+                }
+            }
+            editing.set(false)
+        }
+    };
+    let handle_blur = {
+        let commit = commit.clone();
+        Callback::from(move |_: FocusEvent| commit())
+    };
+    let handle_key_down = Callback::from(move |e: KeyboardEvent| {
+        // keep workspace shortcuts from firing while typing
+        e.stop_propagation();
+        if e.key() == "Escape" || (e.key() == "Enter" && (e.ctrl_key() || e.meta_key())) {
+            e.prevent_default();
+            commit();
+        }
+    });
+
+    let bg_color =
+        Rgb::from_hex_str(&comment.color).unwrap_or(Rgb::new(255., 230., 100., Some(1.0)));
+    let mut bg_color = Hsl::from(&bg_color);
+    bg_color.set_lightness(80.);
+    let body = if *editing {
+        html! {
+            <textarea
+                ref={text_area_ref}
+                aria-label="comment text"
+                value={comment.text.clone()}
+                onblur={handle_blur}
+                onkeydown={handle_key_down}
+                // editing text must not move the comment
+                onpointerdown={Callback::from(|e: PointerEvent| e.stop_propagation())}
+                // clipboard shortcuts apply to the text, not the selected nodes
+                oncopy={Callback::from(|e: yew::Event| e.stop_propagation())}
+                oncut={Callback::from(|e: yew::Event| e.stop_propagation())}
+                onpaste={Callback::from(|e: yew::Event| e.stop_propagation())}
+                class={classes!(
+                    "w-full",
+                    "h-full",
+                    "p-2",
+                    "resize-none",
+                    "bg-transparent",
+                    "focus:outline-none",
+                )}
+            />
+        }
+    } else if comment.markdown {
+        html! {
+            <div ref={markdown_ref} class={classes!("p-2", "overflow-hidden", "select-none")} />
+        }
+    } else {
+        html! {
+            <div class={classes!("p-2", "overflow-hidden", "whitespace-pre-wrap", "select-none")}>
+                {comment.text.clone()}
+            </div>
+        }
+    };
+
+    html! {
+        <div
+            key={comment.id.clone()}
+            tabindex="0"
+            role="note"
+            aria-label={if *selected { "comment (selected)" } else { "comment" }}
+            onpointerdown={handle_pointer_down}
+            onclick={handle_click}
+            ondblclick={handle_double_click}
+            style={format!("width: {width}px; height: {height}px; left: {left}px; top: {top}px; border-color: {border_color}; background: {background};",
+                width = comment.width,
+                height = comment.height,
+                left = comment.x,
+                top = comment.y,
+                border_color = comment.color,
+                background = bg_color.to_css_string(),
+            )}
+            class={classes!(
+                "absolute",
+                "border",
+                "rounded-sm",
+                "shadow-lg",
+                "text-neutral-900",
+                "focus:outline-none",
+                "focus:ring-2",
+                "focus:ring-sky-200",
+                selected.then(|| classes!("ring-2", "ring-sky-400")),
+            )}
+        >
+            {body}
+            <span
+                aria-hidden="true"
+                onpointerdown={handle_resize_pointer_down}
+                class={classes!(
+                    "absolute",
+                    "right-0",
+                    "bottom-0",
+                    "w-3",
+                    "h-3",
+                    "cursor-se-resize",
+                    "border-r-2",
+                    "border-b-2",
+                    "border-neutral-600",
+                )}
+            />
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_raw_html() {
+        let html =
+            markdown_to_html("<script>alert(1)</script>\n\ntext <img src=x onerror=alert(1)>");
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<img"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("&lt;img src=x onerror=alert(1)&gt;"));
+    }
+
+    #[test]
+    fn strips_disallowed_link_schemes() {
+        for url in [
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            " java\tscript:alert(1)",
+            "data:text/html;base64,PHNjcmlwdD4=",
+            "vbscript:msgbox(1)",
+            "file:///etc/passwd",
+        ] {
+            let html = markdown_to_html(&format!("[link](<{}>) ![image](<{}>)", url, url));
+            assert!(html.contains("<a href=\"\">"), "{} kept in {}", url, html);
+            assert!(html.contains("<img src=\"\""), "{} kept in {}", url, html);
+        }
+    }
+
+    #[test]
+    fn keeps_allowed_links() {
+        for url in [
+            "http://example.com",
+            "https://example.com/a?b=c#d",
+            "mailto:someone@example.com",
+            "docs/readme.md",
+            "/absolute/path",
+            "#section",
+        ] {
+            let html = markdown_to_html(&format!("[link]({})", url));
+            assert!(
+                html.contains(&format!("<a href=\"{}\">", url)),
+                "{} dropped in {}",
+                url,
+                html
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use yew::prelude::*;
+
+use crate::{
+    store::{CommentDragActivateCmd, NodeSelectCmd, WorkspaceAction, WorkspaceStore},
+    types::standard_id::StandardId,
+};
+
+use super::{models::Comment, render_comment::RenderComment};
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct RenderCommentListProps {
+    pub comments: Vec<Comment>,
+    /// Ids of the selected nodes and comments.
+    pub selection: Vec<StandardId>,
+    pub dispatcher: UseReducerDispatcher<WorkspaceStore>,
+}
+
+#[function_component(RenderCommentList)]
+pub fn render_comment_list(
+    RenderCommentListProps {
+        comments,
+        selection,
+        dispatcher,
+    }: &RenderCommentListProps,
+) -> Html {
+    let on_comment_pointer_down = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |(comment, e): (Comment, PointerEvent), dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::CommentDragActivate(
+                    CommentDragActivateCmd {
+                        id: comment.id,
+                        x: e.page_x().into(),
+                        y: e.page_y().into(),
+                        resize: false,
+                    },
+                ))
+            },
+            dispatcher,
+        )
+    };
+    let on_comment_resize_pointer_down = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |(comment, e): (Comment, PointerEvent), dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::CommentDragActivate(
+                    CommentDragActivateCmd {
+                        id: comment.id,
+                        x: e.page_x().into(),
+                        y: e.page_y().into(),
+                        resize: true,
+                    },
+                ))
+            },
+            dispatcher,
+        )
+    };
+    let on_comment_click = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |(comment, e): (Comment, MouseEvent), dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::NodeSelect(NodeSelectCmd {
+                    id: comment.id,
+                    additive: e.shift_key() || e.ctrl_key() || e.meta_key(),
+                }))
+            },
+            dispatcher,
+        )
+    };
+    let on_comment_change = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |comment: Comment, dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::CommentUpdate(comment))
+            },
+            dispatcher,
+        )
+    };
+
+    let render_comments = comments
+        .iter()
+        .map(|comment| {
+            html! {
+                <RenderComment
+                    comment={comment.clone()}
+                    selected={selection.contains(&comment.id)}
+                    on_pointer_down={on_comment_pointer_down.clone()}
+                    on_resize_pointer_down={on_comment_resize_pointer_down.clone()}
+                    on_click={on_comment_click.clone()}
+                    on_change={on_comment_change.clone()}
+                />
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <>
+            {render_comments}
+        </>
+    }
+}
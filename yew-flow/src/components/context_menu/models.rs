@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    store::Connector,
+    types::{standard_id::StandardId, standard_unit::StandardUnit},
+};
+
+/// # Context Menu Target
+///
+/// Element a context menu was opened on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContextMenuTarget {
+    /// Empty canvas.
+    Background,
+    /// Node with id.
+    Node(StandardId),
+    /// Edge with id.
+    Edge(StandardId),
+    /// Node connector.
+    Port(Connector),
+}
+
+/// # Context Menu Action
+///
+/// What happens when a context menu item is chosen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ContextMenuAction {
+    /// Built-in: delete the node(s)/edge.
+    Delete,
+    /// Built-in: duplicate the node(s).
+    Duplicate,
+    /// Built-in: remove all edges of the node/connector.
+    DisconnectAll,
+    /// Built-in: collapse the node(s) into a subflow node.
+    CollapseToSubflow,
+    /// Built-in: add a comment at the menu position.
+    AddComment,
+    /// Host defined action with an id. Reported through
+    /// `WorkspaceProps::on_context_menu_select`.
+    Custom(String),
+}
+
+/// # Context Menu Item
+///
+/// Single entry of a context menu.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextMenuItem {
+    pub label: String,
+    pub action: ContextMenuAction,
+}
+
+impl ContextMenuItem {
+    /// Create an item for a host defined action.
+    pub fn custom(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            action: ContextMenuAction::Custom(id.into()),
+        }
+    }
+}
+
+/// # Context Menu Items
+///
+/// Host supplied context menu items per target kind. They are
+/// listed after the built-in items.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContextMenuItems {
+    #[serde(default)]
+    pub background: Vec<ContextMenuItem>,
+    #[serde(default)]
+    pub node: Vec<ContextMenuItem>,
+    #[serde(default)]
+    pub edge: Vec<ContextMenuItem>,
+    #[serde(default)]
+    pub port: Vec<ContextMenuItem>,
+}
+
+impl ContextMenuItems {
+    /// Built-in and host items for `target`.
+    pub fn for_target(&self, target: &ContextMenuTarget) -> Vec<ContextMenuItem> {
+        let builtin = |label: &str, action| ContextMenuItem {
+            label: label.to_string(),
+            action,
+        };
+        let (builtins, custom) = match target {
+            ContextMenuTarget::Background => (
+                vec![builtin("Add comment", ContextMenuAction::AddComment)],
+                &self.background,
+            ),
+            ContextMenuTarget::Node(_) => (
+                vec![
+                    builtin("Duplicate", ContextMenuAction::Duplicate),
+                    builtin("Disconnect all", ContextMenuAction::DisconnectAll),
+                    builtin(
+                        "Collapse into subflow",
+                        ContextMenuAction::CollapseToSubflow,
+                    ),
+                    builtin("Delete", ContextMenuAction::Delete),
+                ],
+                &self.node,
+            ),
+            ContextMenuTarget::Edge(_) => (
+                vec![builtin("Delete", ContextMenuAction::Delete)],
+                &self.edge,
+            ),
+            ContextMenuTarget::Port(_) => (
+                vec![builtin("Disconnect all", ContextMenuAction::DisconnectAll)],
+                &self.port,
+            ),
+        };
+        builtins.into_iter().chain(custom.iter().cloned()).collect()
+    }
+}
+
+/// # Context Menu
+///
+/// Open context menu. Position is in flow coordinates so the
+/// menu stays attached to its target while panning/zooming.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextMenu {
+    pub target: ContextMenuTarget,
+    pub x: StandardUnit,
+    pub y: StandardUnit,
+}
+
+/// # Context Menu Select Event
+///
+/// Sent to the host when a custom context menu item is chosen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextMenuSelectEvent {
+    /// Id of the chosen custom item.
+    pub id: String,
+    pub target: ContextMenuTarget,
+}
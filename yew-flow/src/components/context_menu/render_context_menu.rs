@@ -0,0 +1,113 @@
+use yew::prelude::*;
+
+use crate::{
+    components::viewport::models::{ContainerDimensions, ViewportTransform},
+    constants::{CONTEXT_MENU_ITEM_HEIGHT, CONTEXT_MENU_WIDTH},
+};
+
+use super::models::{ContextMenu, ContextMenuItem};
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct RenderContextMenuProps {
+    pub menu: ContextMenu,
+    pub items: Vec<ContextMenuItem>,
+    /// Pan/zoom used to place the menu on the screen.
+    pub transform: ViewportTransform,
+    /// Container the menu is clamped to.
+    pub dimensions: ContainerDimensions,
+    pub on_select: Callback<ContextMenuItem>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(RenderContextMenu)]
+pub fn render_context_menu(
+    RenderContextMenuProps {
+        menu,
+        items,
+        transform,
+        dimensions,
+        on_select,
+        on_close,
+    }: &RenderContextMenuProps,
+) -> Html {
+    if items.is_empty() {
+        return html! {};
+    }
+
+    // flow -> container position, kept inside the container
+    let height = CONTEXT_MENU_ITEM_HEIGHT * items.len() as f64;
+    let left = (menu.x * transform.zoom + transform.x)
+        .min(dimensions.width - CONTEXT_MENU_WIDTH)
+        .max(0.);
+    let top = (menu.y * transform.zoom + transform.y)
+        .min(dimensions.height - height)
+        .max(0.);
+
+    let handle_key_down = {
+        let on_close = on_close.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Escape" {
+                e.stop_propagation();
+                on_close.emit(())
+            }
+        })
+    };
+    let render_items = items
+        .iter()
+        .map(|item| {
+            let handle_click = {
+                let on_select = on_select.clone();
+                let item = item.clone();
+                Callback::from(move |e: MouseEvent| {
+                    e.stop_propagation();
+                    on_select.emit(item.clone())
+                })
+            };
+            html! {
+                <button
+                    role="menuitem"
+                    onclick={handle_click}
+                    style={format!("height: {}px;", CONTEXT_MENU_ITEM_HEIGHT)}
+                    class={classes!(
+                        "w-full",
+                        "px-3",
+                        "text-left",
+                        "hover:bg-neutral-600",
+                        "focus:outline-none",
+                        "focus:bg-neutral-600",
+                    )}
+                >
+                    {item.label.clone()}
+                </button>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div
+            role="menu"
+            // keep interactions with the menu away from the workspace
+            onpointerdown={Callback::from(|e: PointerEvent| e.stop_propagation())}
+            oncontextmenu={Callback::from(|e: MouseEvent| {
+                e.prevent_default();
+                e.stop_propagation();
+            })}
+            onkeydown={handle_key_down}
+            style={format!("left: {}px; top: {}px; width: {}px;", left, top, CONTEXT_MENU_WIDTH)}
+            class={classes!(
+                "absolute",
+                "z-10",
+                "flex",
+                "flex-col",
+                "bg-neutral-700",
+                "border",
+                "border-neutral-400",
+                "rounded-sm",
+                "shadow-lg",
+                "select-none",
+            )}
+        >
+            {render_items}
+        </div>
+    }
+}
@@ -7,8 +7,28 @@ use crate::types::{
     standard_unit::StandardUnit,
 };
 
+/// # Edge Activity
+///
+/// Activity state of an edge. Used to visualize data
+/// flowing through the connection.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum EdgeActivity {
+    /// No data is flowing through the edge.
+    Idle,
+    /// Data is currently flowing through the edge.
+    Active,
+}
+
+impl Default for EdgeActivity {
+    fn default() -> Self {
+        EdgeActivity::Idle
+    }
+}
+
 /// Edge
 #[derive(Clone, PartialEq, Properties, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Edge {
     pub id: StandardId,
     /// From input id.
@@ -25,6 +45,12 @@ pub struct Edge {
     pub y2: StandardUnit,
     /// Edge color css string
     pub color: String,
+    /// Render the edge with moving dashes.
+    #[serde(default)]
+    pub animated: bool,
+    /// Activity state of the edge. Renders a traveling dot when active.
+    #[serde(default)]
+    pub activity: EdgeActivity,
 }
 
 impl Default for Edge {
@@ -38,6 +64,8 @@ impl Default for Edge {
             x2: Default::default(),
             y2: Default::default(),
             color: Hsl::new(0., 100., 100., Some(0.8)).to_css_string(),
+            animated: false,
+            activity: Default::default(),
         }
     }
 }
@@ -1,16 +1,54 @@
 use yew::prelude::*;
 
-use crate::constants::{NODE_HEIGHT, NODE_WIDTH};
+use crate::{
+    components::node::models::PortSide, constants::NODE_WIDTH, store::EdgeEnd,
+    utils::capture_pointer,
+};
 
-use super::models::Edge;
+use super::models::{Edge, EdgeActivity};
 
 #[derive(Clone, Properties, PartialEq)]
 pub struct RenderEdgeProps {
     pub edge: Edge,
+    /// Side of the node the edge leaves from.
+    pub from_side: PortSide,
+    /// Side of the node the edge enters.
+    pub to_side: PortSide,
+    /// Whether the edge is currently hovered.
+    pub hovered: bool,
+    /// Whether the edge reacts to pointer events.
+    pub interactive: bool,
+    /// Whether the edge is emphasized by the current highlight.
+    pub highlighted: bool,
+    /// Whether the edge is outside the current highlight.
+    pub dimmed: bool,
+    pub on_mouse_enter: Callback<Edge>,
+    pub on_mouse_leave: Callback<Edge>,
+    pub on_click: Callback<Edge>,
+    pub on_double_click: Callback<Edge>,
+    /// Called when an end handle of the edge is grabbed.
+    pub on_end_pointer_down: Callback<(Edge, EdgeEnd)>,
+    pub on_context_menu: Callback<(Edge, MouseEvent)>,
 }
 
 #[function_component(RenderEdge)]
-pub fn render_edge(RenderEdgeProps { edge }: &RenderEdgeProps) -> Html {
+pub fn render_edge(
+    RenderEdgeProps {
+        edge,
+        from_side,
+        to_side,
+        hovered,
+        interactive,
+        highlighted,
+        dimmed,
+        on_mouse_enter,
+        on_mouse_leave,
+        on_click,
+        on_double_click,
+        on_end_pointer_down,
+        on_context_menu,
+    }: &RenderEdgeProps,
+) -> Html {
     // log::info!("render_edge: {}", edge.id);
     let Edge {
         id,
@@ -21,30 +59,155 @@ pub fn render_edge(RenderEdgeProps { edge }: &RenderEdgeProps) -> Html {
         x2,
         y2,
         color,
+        animated,
+        activity,
     } = edge;
 
-    let sx1 = x1 + (NODE_WIDTH / 2.);
-    let sy1 = y1;
-    let sx2 = (x2 - (NODE_WIDTH / 2.)).max(0.);
-    let sy2 = y2;
+    // leave/enter perpendicular to the side of the connectors
+    let (dx1, dy1) = from_side.direction();
+    let (dx2, dy2) = to_side.direction();
+    let sx1 = x1 + dx1 * (NODE_WIDTH / 2.);
+    let sy1 = y1 + dy1 * (NODE_WIDTH / 2.);
+    let sx2 = x2 + dx2 * (NODE_WIDTH / 2.);
+    let sy2 = y2 + dy2 * (NODE_WIDTH / 2.);
+
+    let path = format!(
+        "M {x1} {y1} C {sx1} {sy1}, {sx2} {sy2}, {x2} {y2}",
+        x1 = x1,
+        y1 = y1,
+        sx1 = sx1,
+        sy1 = sy1,
+        sx2 = sx2,
+        sy2 = sy2,
+        x2 = x2,
+        y2 = y2,
+    );
+
+    let handle_mouse_enter = {
+        let on_mouse_enter = on_mouse_enter.clone();
+        let edge = edge.clone();
+        Callback::from(move |_: MouseEvent| on_mouse_enter.emit(edge.clone()))
+    };
+    let handle_mouse_leave = {
+        let on_mouse_leave = on_mouse_leave.clone();
+        let edge = edge.clone();
+        Callback::from(move |_: MouseEvent| on_mouse_leave.emit(edge.clone()))
+    };
+    let handle_click = {
+        let on_click = on_click.clone();
+        let edge = edge.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            on_click.emit(edge.clone())
+        })
+    };
+    let handle_double_click = {
+        let on_double_click = on_double_click.clone();
+        let edge = edge.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            on_double_click.emit(edge.clone())
+        })
+    };
+    let handle_context_menu = {
+        let on_context_menu = on_context_menu.clone();
+        let edge = edge.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            e.stop_propagation();
+            on_context_menu.emit((edge.clone(), e))
+        })
+    };
+    let render_end_handle = |end: EdgeEnd, cx: f64, cy: f64| {
+        let handle_pointer_down = {
+            let on_end_pointer_down = on_end_pointer_down.clone();
+            let edge = edge.clone();
+            let end = end.clone();
+            Callback::from(move |e: PointerEvent| {
+                // let secondary pointers bubble up for multi-touch gestures
+                if !e.is_primary() {
+                    return;
+                }
+                e.stop_propagation();
+                capture_pointer(&e);
+                on_end_pointer_down.emit((edge.clone(), end.clone()))
+            })
+        };
+        html! {
+            <circle
+                cx={cx.to_string()}
+                cy={cy.to_string()}
+                r="6"
+                fill={if *hovered { "deepskyblue" } else { "transparent" }}
+                pointer-events={if *interactive { "all" } else { "none" }}
+                class={classes!("cursor-move")}
+                onpointerdown={handle_pointer_down}
+            />
+        }
+    };
+    // only fully connected edges can be reconnected
+    let render_end_handles = if from_output.is_some() && to_input.is_some() {
+        html! {
+            <>
+                {render_end_handle(EdgeEnd::From, *x1, *y1)}
+                {render_end_handle(EdgeEnd::To, *x2, *y2)}
+            </>
+        }
+    } else {
+        html! {}
+    };
 
     html! {
-        <path
-            key={edge.id.clone()}
-            d={format!(
-                "M {x1} {y1} C {sx1} {sy1}, {sx2} {sy2}, {x2} {y2}",
-                x1=x1,
-                y1=y1,
-                sx1=sx1,
-                sy1=sy1,
-                sx2=sx2,
-                sy2=sy2,
-                x2=x2,
-                y2=y2,
-            )}
-            stroke="blue"
-            stroke-width="4px"
-            fill="transparent"
-        />
+        <g key={edge.id.clone()}>
+            <path
+                d={path.clone()}
+                pointer-events="none"
+                stroke={match (*hovered, *highlighted) {
+                    (true, _) => "deepskyblue",
+                    (false, true) => "orange",
+                    (false, false) => "blue",
+                }}
+                stroke-width={if *hovered || *highlighted { "6px" } else { "4px" }}
+                stroke-opacity={dimmed.then(|| "0.3")}
+                stroke-dasharray={animated.then(|| "8 4")}
+                fill="transparent"
+            >
+                if *animated {
+                    // move the dashes along the path
+                    <animate
+                        attributeName="stroke-dashoffset"
+                        from="12"
+                        to="0"
+                        dur="0.4s"
+                        repeatCount="indefinite"
+                    />
+                }
+            </path>
+            // wider invisible path for easier hit-testing
+            <path
+                d={path.clone()}
+                stroke="transparent"
+                stroke-width="16px"
+                fill="none"
+                pointer-events={if *interactive { "stroke" } else { "none" }}
+                class={classes!("cursor-pointer")}
+                onmouseenter={handle_mouse_enter}
+                onmouseleave={handle_mouse_leave}
+                onclick={handle_click}
+                ondblclick={handle_double_click}
+                oncontextmenu={handle_context_menu}
+            />
+            {render_end_handles}
+            if *activity == EdgeActivity::Active {
+                // dot traveling from output to input
+                <circle r="4" fill="white">
+                    <animateMotion
+                        path={path}
+                        dur="1.5s"
+                        repeatCount="indefinite"
+                    />
+                </circle>
+            }
+        </g>
     }
 }
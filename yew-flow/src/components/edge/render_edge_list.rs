@@ -1,24 +1,141 @@
+use std::collections::HashMap;
+
 use yew::prelude::*;
 
-use crate::components::edge::render_edge::RenderEdge;
+use crate::{
+    components::{
+        context_menu::models::ContextMenuTarget,
+        edge::render_edge::RenderEdge,
+        node::models::{Node, PortSide},
+    },
+    graph::Highlight,
+    store::{
+        ContextMenuOpenCmd, EdgeEnd, EdgeUpdateActivateCmd, InteractionMode, WorkspaceAction,
+        WorkspaceStore,
+    },
+};
 
 use super::models::Edge;
 
 #[derive(Clone, Properties, PartialEq)]
 pub struct RenderEdgeListProps {
     pub edges: Vec<Edge>,
+    /// Nodes the edges are connected to. Used to find the sides of their connectors.
+    pub nodes: Vec<Node>,
+    pub interaction_mode: InteractionMode,
+    /// Edges to emphasize, the others are dimmed.
+    pub highlight: Option<Highlight>,
+    pub dispatcher: UseReducerDispatcher<WorkspaceStore>,
+    pub on_edge_click: Callback<Edge>,
+    pub on_edge_double_click: Callback<Edge>,
 }
 
 #[function_component(RenderEdgeList)]
-pub fn render_edge_list(RenderEdgeListProps { edges }: &RenderEdgeListProps) -> Html {
+pub fn render_edge_list(
+    RenderEdgeListProps {
+        edges,
+        nodes,
+        interaction_mode,
+        highlight,
+        dispatcher,
+        on_edge_click,
+        on_edge_double_click,
+    }: &RenderEdgeListProps,
+) -> Html {
+    let on_edge_mouse_enter = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |edge: Edge, dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::EdgeHoverActivate(edge.id))
+            },
+            dispatcher,
+        )
+    };
+    let on_edge_mouse_leave = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |edge: Edge, dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::EdgeHoverDeactivate(edge.id))
+            },
+            dispatcher,
+        )
+    };
+    let on_edge_end_pointer_down = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |(edge, end): (Edge, EdgeEnd), dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::EdgeUpdateActivate(EdgeUpdateActivateCmd {
+                    edge_id: edge.id,
+                    end,
+                }))
+            },
+            dispatcher,
+        )
+    };
+    let on_edge_context_menu = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |(edge, e): (Edge, MouseEvent), dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::ContextMenuOpen(ContextMenuOpenCmd {
+                    target: ContextMenuTarget::Edge(edge.id),
+                    x: e.page_x().into(),
+                    y: e.page_y().into(),
+                }))
+            },
+            dispatcher,
+        )
+    };
+
+    // edges should not steal pointer events while dragging nodes/edges
+    let interactive = matches!(
+        interaction_mode,
+        InteractionMode::None | InteractionMode::EdgeHover(_)
+    );
+    let port_sides = nodes
+        .iter()
+        .flat_map(|node| {
+            let inputs = node.inputs.iter().map(|input| (&input.id, input.side));
+            let outputs = node.outputs.iter().map(|output| (&output.id, output.side));
+            inputs.chain(outputs)
+        })
+        .collect::<HashMap<_, _>>();
     let render_edges = {
         edges
             .clone()
             .iter()
             .map(|edge| {
+                let hovered = *interaction_mode == InteractionMode::EdgeHover(edge.id.clone());
+                let from_side = edge
+                    .from_output
+                    .as_ref()
+                    .and_then(|id| port_sides.get(id).copied());
+                let to_side = edge
+                    .to_input
+                    .as_ref()
+                    .and_then(|id| port_sides.get(id).copied());
+                // a loose end faces the connected end
+                let from_side = from_side
+                    .or_else(|| to_side.map(|side| side.opposite()))
+                    .unwrap_or(PortSide::Right);
+                let to_side = to_side.unwrap_or_else(|| from_side.opposite());
+                let highlighted = highlight
+                    .as_ref()
+                    .map(|highlight| highlight.edges.contains(&edge.id));
                 html! {
                     <RenderEdge
                         edge={edge.clone()}
+                        {from_side}
+                        {to_side}
+                        {hovered}
+                        {interactive}
+                        highlighted={highlighted == Some(true)}
+                        dimmed={highlighted == Some(false)}
+                        on_mouse_enter={on_edge_mouse_enter.clone()}
+                        on_mouse_leave={on_edge_mouse_leave.clone()}
+                        on_click={on_edge_click.clone()}
+                        on_double_click={on_edge_double_click.clone()}
+                        on_end_pointer_down={on_edge_end_pointer_down.clone()}
+                        on_context_menu={on_edge_context_menu.clone()}
                     />
                 }
             })
@@ -26,7 +143,19 @@ pub fn render_edge_list(RenderEdgeListProps { edges }: &RenderEdgeListProps) ->
     };
 
     html! {
-        <svg width="100%" height="100%" xmlns="http://www.w3.org/2000/svg">
+        <svg
+            width="100%"
+            height="100%"
+            xmlns="http://www.w3.org/2000/svg"
+            // only the edge paths themselves react to pointer events
+            class={classes!(
+                "absolute",
+                "top-0",
+                "left-0",
+                "overflow-visible",
+                "pointer-events-none",
+            )}
+        >
             // <path
             //     d="M 100 0 C 200 0, 0 100, 100 100"
             //     stroke="blue"
@@ -0,0 +1,3 @@
+pub mod models;
+pub mod render_group;
+pub mod render_group_list;
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::node::models::Node,
+    constants::{GROUP_HEADER_HEIGHT, NODE_HEIGHT, NODE_WIDTH},
+    types::{standard_id::StandardId, standard_unit::StandardUnit},
+};
+
+/// # Group
+///
+/// Frame containing nodes. Moving the frame moves its children.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Group {
+    pub id: StandardId,
+    pub title: String,
+    /// Hex color of the frame, e.g. `#64748b`.
+    pub color: String,
+    pub x: StandardUnit,
+    pub y: StandardUnit,
+    pub width: StandardUnit,
+    pub height: StandardUnit,
+    /// Collapsed groups only show their header and hide their children.
+    #[serde(default)]
+    pub collapsed: bool,
+    /// Ids of the nodes inside the group.
+    #[serde(default)]
+    pub children: Vec<StandardId>,
+}
+
+impl Group {
+    /// Whether the center of `node` is inside the body of the group.
+    pub fn contains(&self, node: &Node) -> bool {
+        let x = node.x + NODE_WIDTH / 2.;
+        let y = node.y + NODE_HEIGHT / 2.;
+        !self.collapsed
+            && x >= self.x
+            && x <= self.x + self.width
+            && y >= self.y + GROUP_HEADER_HEIGHT
+            && y <= self.y + self.height
+    }
+}
@@ -0,0 +1,102 @@
+use colorsys::{ColorAlpha, Hsl, Rgb};
+use yew::prelude::*;
+
+use crate::{constants::GROUP_HEADER_HEIGHT, utils::capture_pointer};
+
+use super::models::Group;
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct RenderGroupProps {
+    pub group: Group,
+    /// Called when the header is grabbed to move the group.
+    pub on_header_pointer_down: Callback<(Group, PointerEvent)>,
+    pub on_toggle_collapse: Callback<Group>,
+}
+
+#[function_component(RenderGroup)]
+pub fn render_group(
+    RenderGroupProps {
+        group,
+        on_header_pointer_down,
+        on_toggle_collapse,
+    }: &RenderGroupProps,
+) -> Html {
+    let handle_header_pointer_down = {
+        let on_header_pointer_down = on_header_pointer_down.clone();
+        let group = group.clone();
+        Callback::from(move |e: PointerEvent| {
+            // let secondary pointers bubble up for multi-touch gestures
+            if !e.is_primary() {
+                return;
+            }
+            e.stop_propagation();
+            capture_pointer(&e);
+            on_header_pointer_down.emit((group.clone(), e))
+        })
+    };
+    let handle_toggle_collapse = {
+        let on_toggle_collapse = on_toggle_collapse.clone();
+        let group = group.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            on_toggle_collapse.emit(group.clone())
+        })
+    };
+
+    let bg_color = Rgb::from_hex_str(&group.color).unwrap_or(Rgb::new(100., 100., 100., Some(1.0)));
+    let mut bg_color = Hsl::from(&bg_color);
+    bg_color.set_lightness(20.);
+    bg_color.set_alpha(0.4);
+    let height = if group.collapsed {
+        GROUP_HEADER_HEIGHT
+    } else {
+        group.height
+    };
+    html! {
+        <div
+            key={group.id.clone()}
+            role="group"
+            aria-roledescription="frame"
+            aria-label={group.title.clone()}
+            style={format!("width: {width}px; height: {height}px; left: {left}px; top: {top}px; border-color: {border_color}; background: {background};",
+                width = group.width,
+                height = height,
+                left = group.x,
+                top = group.y,
+                border_color = group.color,
+                background = bg_color.to_css_string(),
+            )}
+            // the body lets pointer events through to the canvas
+            class={classes!(
+                "absolute",
+                "border-2",
+                "rounded-lg",
+                "pointer-events-none",
+            )}
+        >
+            <div
+                onpointerdown={handle_header_pointer_down}
+                style={format!("height: {}px; background: {};", GROUP_HEADER_HEIGHT, group.color)}
+                class={classes!(
+                    "flex",
+                    "items-center",
+                    "px-2",
+                    "select-none",
+                    "cursor-move",
+                    "pointer-events-auto",
+                )}
+            >
+                <button
+                    aria-label={if group.collapsed { "expand" } else { "collapse" }}
+                    aria-expanded={(!group.collapsed).to_string()}
+                    onpointerdown={Callback::from(|e: PointerEvent| e.stop_propagation())}
+                    onclick={handle_toggle_collapse}
+                    class={classes!("mr-2", "focus:outline-none", "focus:ring-2", "focus:ring-sky-200")}
+                >
+                    {if group.collapsed { "▸" } else { "▾" }}
+                </button>
+                {group.title.clone()}
+            </div>
+        </div>
+    }
+}
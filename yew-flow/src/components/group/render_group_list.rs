@@ -0,0 +1,58 @@
+use yew::prelude::*;
+
+use crate::store::{GroupDragActivateCmd, WorkspaceAction, WorkspaceStore};
+
+use super::{models::Group, render_group::RenderGroup};
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct RenderGroupListProps {
+    pub groups: Vec<Group>,
+    pub dispatcher: UseReducerDispatcher<WorkspaceStore>,
+}
+
+#[function_component(RenderGroupList)]
+pub fn render_group_list(
+    RenderGroupListProps { groups, dispatcher }: &RenderGroupListProps,
+) -> Html {
+    let on_header_pointer_down = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |(group, e): (Group, PointerEvent), dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::GroupDragActivate(GroupDragActivateCmd {
+                    id: group.id,
+                    x: e.page_x().into(),
+                    y: e.page_y().into(),
+                }))
+            },
+            dispatcher,
+        )
+    };
+    let on_toggle_collapse = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |group: Group, dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::GroupToggleCollapse(group.id))
+            },
+            dispatcher,
+        )
+    };
+
+    let render_groups = groups
+        .iter()
+        .map(|group| {
+            html! {
+                <RenderGroup
+                    group={group.clone()}
+                    on_header_pointer_down={on_header_pointer_down.clone()}
+                    on_toggle_collapse={on_toggle_collapse.clone()}
+                />
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <>
+            {render_groups}
+        </>
+    }
+}
@@ -1,3 +1,8 @@
+pub mod comment;
+pub mod context_menu;
 pub mod edge;
+pub mod group;
 pub mod node;
+pub mod quick_add;
+pub mod subflow;
 pub mod viewport;
\ No newline at end of file
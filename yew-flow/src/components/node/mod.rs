@@ -1,3 +1,5 @@
 pub mod models;
+pub mod node_palette;
 pub mod render_node;
+pub mod render_node_field;
 pub mod render_node_list;
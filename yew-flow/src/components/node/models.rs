@@ -1,29 +1,374 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use yew::prelude::*;
 
-use crate::types::{standard_id::StandardId, standard_unit::StandardUnit};
+use crate::{
+    constants::{COLLAPSED_NODE_HEIGHT, NODE_FIELD_HEIGHT, NODE_HEIGHT},
+    types::{
+        standard_id::{IdentifierExt, StandardId},
+        standard_unit::StandardUnit,
+    },
+    workspace::YewFlowValues,
+};
+
+/// # Port Side
+///
+/// Side of the node a connector is placed on.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum PortSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl PortSide {
+    /// Unit vector pointing away from the node on this side.
+    /// Edges leave/enter connectors along it.
+    pub fn direction(&self) -> (StandardUnit, StandardUnit) {
+        match self {
+            PortSide::Left => (-1., 0.),
+            PortSide::Right => (1., 0.),
+            PortSide::Top => (0., -1.),
+            PortSide::Bottom => (0., 1.),
+        }
+    }
+
+    /// The side across the node.
+    pub fn opposite(&self) -> Self {
+        match self {
+            PortSide::Left => PortSide::Right,
+            PortSide::Right => PortSide::Left,
+            PortSide::Top => PortSide::Bottom,
+            PortSide::Bottom => PortSide::Top,
+        }
+    }
+
+    /// Whether connectors on this side are stacked vertically.
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, PortSide::Left | PortSide::Right)
+    }
+}
+
+fn default_input_side() -> PortSide {
+    PortSide::Left
+}
+
+fn default_output_side() -> PortSide {
+    PortSide::Right
+}
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct NodeInput {
     pub id: StandardId,
+    /// Name shown next to the connector.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Side of the node the connector is on. Defaults to the left side.
+    #[serde(default = "default_input_side")]
+    pub side: PortSide,
+    /// Explicit distance in px from the top (left/right sides) or the left
+    /// (top/bottom sides) of the node. Connectors are spread evenly without it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<StandardUnit>,
+    /// Editor shown next to the connector while no edge is connected to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub widget: Option<FieldKind>,
+    /// Value used while no edge is connected to the connector.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<FieldValue>,
+    /// Id of the inner connector a connector of a subflow node forwards to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exposes: Option<StandardId>,
     #[serde(skip)]
     pub reference: NodeRef,
 }
 
+impl NodeInput {
+    /// Create an unlabeled input on the left side.
+    pub fn new(id: StandardId) -> Self {
+        Self {
+            id,
+            label: None,
+            side: default_input_side(),
+            offset: None,
+            widget: None,
+            value: None,
+            exposes: None,
+            reference: NodeRef::default(),
+        }
+    }
+
+    /// Id of the inner connector a subflow node's input forwards to. Flows
+    /// saved before `exposes` existed reused the inner id for the connector.
+    pub fn exposed_id(&self) -> &StandardId {
+        self.exposes.as_ref().unwrap_or(&self.id)
+    }
+
+    /// Value of the input while unconnected, falling back
+    /// to the default of its widget.
+    pub fn inline_value(&self) -> Option<FieldValue> {
+        self.value
+            .clone()
+            .or_else(|| self.widget.as_ref().map(|widget| widget.default_value()))
+    }
+}
+
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct NodeOutput {
     pub id: StandardId,
+    /// Name shown next to the connector.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Side of the node the connector is on. Defaults to the right side.
+    #[serde(default = "default_output_side")]
+    pub side: PortSide,
+    /// Explicit distance in px from the top (left/right sides) or the left
+    /// (top/bottom sides) of the node. Connectors are spread evenly without it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<StandardUnit>,
+    /// Id of the inner connector a connector of a subflow node forwards from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exposes: Option<StandardId>,
     #[serde(skip)]
     pub reference: NodeRef,
 }
 
+impl NodeOutput {
+    /// Create an unlabeled output on the right side.
+    pub fn new(id: StandardId) -> Self {
+        Self {
+            id,
+            label: None,
+            side: default_output_side(),
+            offset: None,
+            exposes: None,
+            reference: NodeRef::default(),
+        }
+    }
+
+    /// Id of the inner connector a subflow node's output forwards from.
+    /// Flows saved before `exposes` existed reused the inner id for the connector.
+    pub fn exposed_id(&self) -> &StandardId {
+        self.exposes.as_ref().unwrap_or(&self.id)
+    }
+}
+
+/// # Field Kind
+///
+/// Editor control of a node field.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FieldKind {
+    /// Single line text input.
+    Text,
+    /// Number input, optionally limited to a range.
+    Number {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        step: Option<f64>,
+    },
+    /// On/off checkbox.
+    Checkbox,
+    /// Choice between `options`.
+    Select { options: Vec<String> },
+    /// Color picker with a hex color string value.
+    Color,
+}
+
+impl FieldKind {
+    /// Value of a field that has not been edited yet.
+    pub fn default_value(&self) -> FieldValue {
+        match self {
+            FieldKind::Text => FieldValue::Text(String::new()),
+            FieldKind::Number { min, max, .. } => FieldValue::Number(
+                0f64.max(min.unwrap_or(f64::MIN))
+                    .min(max.unwrap_or(f64::MAX)),
+            ),
+            FieldKind::Checkbox => FieldValue::Bool(false),
+            FieldKind::Select { options } => {
+                FieldValue::Text(options.first().cloned().unwrap_or_default())
+            }
+            FieldKind::Color => FieldValue::Text("#000000".to_string()),
+        }
+    }
+}
+
+/// # Field Value
+///
+/// Value of a node field. Serialized as a plain JSON value.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum FieldValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+/// # Node Field
+///
+/// Declares an editable parameter shown in the node body.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NodeField {
+    /// Key of the value in `Node::values`.
+    pub key: String,
+    pub label: String,
+    pub kind: FieldKind,
+}
+
+/// # Node Status
+///
+/// Execution state of a node.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum NodeStatus {
+    /// Not running, e.g. waiting for its inputs.
+    Idle,
+    /// Currently computing its outputs.
+    Running,
+    /// Outputs are up to date.
+    Done,
+    /// Computing the outputs failed with a message.
+    Error(String),
+}
+
+impl Default for NodeStatus {
+    fn default() -> Self {
+        NodeStatus::Idle
+    }
+}
+
 #[derive(Clone, PartialEq, Properties, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Node {
     pub id: StandardId,
+    /// Type of the node. Used to look up its compute function
+    /// when the flow is evaluated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
     pub title: String,
     pub x: StandardUnit,
     pub y: StandardUnit,
+    /// Hex color of the node border and body, e.g. `#7c3aed`.
     pub color: String,
     pub inputs: Vec<NodeInput>,
     pub outputs: Vec<NodeOutput>,
+    /// Inner graph of a subflow node. Its connectors link to the
+    /// inner connectors they expose through `exposes`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subflow: Option<Box<YewFlowValues>>,
+    /// Collapsed nodes only show their header, with their
+    /// connectors merged into one anchor per side.
+    #[serde(default)]
+    pub collapsed: bool,
+    /// Editable parameters shown in the node body.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<NodeField>,
+    /// Values of the fields by key.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub values: BTreeMap<String, FieldValue>,
+    /// Execution state of the node. Rendered as a badge.
+    #[serde(default)]
+    pub status: NodeStatus,
+    /// Progress of a running node in percent. Rendered as a progress bar.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<f64>,
+}
+
+impl Node {
+    /// Current value of `field`, falling back to the default of its kind.
+    pub fn field_value(&self, field: &NodeField) -> FieldValue {
+        self.values
+            .get(&field.key)
+            .cloned()
+            .unwrap_or_else(|| field.kind.default_value())
+    }
+
+    /// Rendered height of the node.
+    pub fn height(&self) -> StandardUnit {
+        if self.collapsed {
+            COLLAPSED_NODE_HEIGHT
+        } else {
+            NODE_HEIGHT + NODE_FIELD_HEIGHT * self.fields.len() as StandardUnit
+        }
+    }
+
+    /// Side of the connector with `id` if it belongs to the node.
+    pub fn port_side(&self, id: &StandardId) -> Option<PortSide> {
+        self.inputs
+            .iter()
+            .find(|input| input.id == *id)
+            .map(|input| input.side)
+            .or_else(|| {
+                self.outputs
+                    .iter()
+                    .find(|output| output.id == *id)
+                    .map(|output| output.side)
+            })
+    }
+
+    /// Ids of all inputs and outputs of the node.
+    pub fn port_ids(&self) -> Vec<StandardId> {
+        self.inputs
+            .iter()
+            .map(|input| input.id.clone())
+            .chain(self.outputs.iter().map(|output| output.id.clone()))
+            .collect()
+    }
+}
+
+/// # Node Template
+///
+/// Blueprint of a node that can be added from the UI.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct NodeTemplate {
+    /// Type of the created nodes.
+    #[serde(default)]
+    pub kind: Option<String>,
+    pub title: String,
+    pub color: String,
+    /// Number of inputs.
+    pub inputs: usize,
+    /// Number of outputs.
+    pub outputs: usize,
+    /// Editable parameters of the created nodes.
+    #[serde(default)]
+    pub fields: Vec<NodeField>,
+}
+
+impl NodeTemplate {
+    /// Create a new node from the template at (`x`, `y`)
+    /// with freshly generated node and port ids.
+    pub fn instantiate(&self, x: StandardUnit, y: StandardUnit) -> Node {
+        Node {
+            id: StandardId::generate(),
+            kind: self.kind.clone(),
+            title: self.title.clone(),
+            x,
+            y,
+            color: self.color.clone(),
+            inputs: (0..self.inputs)
+                .map(|_| NodeInput::new(StandardId::generate()))
+                .collect(),
+            outputs: (0..self.outputs)
+                .map(|_| NodeOutput::new(StandardId::generate()))
+                .collect(),
+            subflow: None,
+            collapsed: false,
+            fields: self.fields.clone(),
+            values: BTreeMap::new(),
+            status: Default::default(),
+            progress: None,
+        }
+    }
 }
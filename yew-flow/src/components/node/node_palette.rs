@@ -0,0 +1,82 @@
+use yew::prelude::*;
+
+use super::models::NodeTemplate;
+
+/// Drag data type used to transfer templates from the palette to the workspace.
+pub const NODE_TEMPLATE_MIME_TYPE: &str = "application/x-yew-flow-node-template";
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct NodePaletteProps {
+    pub templates: Vec<NodeTemplate>,
+}
+
+/// # Node Palette
+///
+/// List of node templates that can be dragged onto the `Workspace`
+/// to create new nodes.
+#[function_component(NodePalette)]
+pub fn node_palette(NodePaletteProps { templates }: &NodePaletteProps) -> Html {
+    let render_templates = templates
+        .iter()
+        .map(|template| {
+            let handle_drag_start = {
+                let template = template.clone();
+                Callback::from(move |e: DragEvent| {
+                    let data = match e.data_transfer() {
+                        Some(data) => data,
+                        None => return,
+                    };
+                    match serde_json::to_string(&template) {
+                        Ok(json) => {
+                            let _ = data.set_data(NODE_TEMPLATE_MIME_TYPE, &json);
+                            data.set_effect_allowed("copy");
+                        }
+                        Err(err) => log::error!("failed to drag node template: {}", err),
+                    }
+                })
+            };
+            html! {
+                <li
+                    key={template.title.clone()}
+                    draggable="true"
+                    ondragstart={handle_drag_start}
+                    title={format!(
+                        "{} ({} inputs, {} outputs)",
+                        template.title, template.inputs, template.outputs
+                    )}
+                    style={format!("border-color: {};", template.color)}
+                    class={classes!(
+                        "px-2",
+                        "py-1",
+                        "mb-1",
+                        "border-l-4",
+                        "rounded-sm",
+                        "bg-neutral-700",
+                        "cursor-grab",
+                        "select-none",
+                    )}
+                >
+                    {template.title.clone()}
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <ul
+            aria-label="node palette"
+            class={classes!(
+                "flex",
+                "flex-col",
+                "p-1",
+                "text-neutral-50",
+                "bg-neutral-800",
+                "border-neutral-400",
+                "border-2",
+                "rounded-sm",
+            )}
+        >
+            {render_templates}
+        </ul>
+    }
+}
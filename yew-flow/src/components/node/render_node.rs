@@ -1,132 +1,513 @@
+use std::slice;
+
 use colorsys::{Hsl, Rgb};
 use yew::prelude::*;
 
-use crate::constants::{NODE_HEIGHT, NODE_WIDTH};
+use crate::{
+    components::context_menu::models::ContextMenuTarget,
+    constants::NODE_WIDTH,
+    store::{connector_label, Connector},
+    types::{standard_id::StandardId, standard_unit::StandardUnit},
+    utils::capture_pointer,
+};
 
-use super::models::{Node, NodeInput, NodeOutput};
+use super::{
+    models::{FieldValue, Node, NodeField, NodeInput, NodeOutput, NodeStatus, PortSide},
+    render_node_field::RenderNodeField,
+};
 
 #[derive(Clone, Properties, PartialEq)]
 pub struct RenderNodeProps {
     pub node: Node,
-    pub on_mouse_down: Callback<Node>,
-    pub on_input_mouse_down: Callback<NodeInput>,
-    pub on_input_mouse_up: Callback<NodeInput>,
-    pub on_output_mouse_down: Callback<NodeOutput>,
-    pub on_output_mouse_up: Callback<NodeOutput>,
-    pub on_mouse_up: Callback<Node>,
-    pub on_click: Callback<Node>,
+    /// Id of the connector to highlight as the target of a dragged edge.
+    pub highlighted_port: Option<String>,
+    pub on_pointer_down: Callback<Node>,
+    pub on_input_pointer_down: Callback<(NodeInput, PointerEvent)>,
+    pub on_output_pointer_down: Callback<(NodeOutput, PointerEvent)>,
+    pub on_click: Callback<(Node, MouseEvent)>,
+    pub on_double_click: Callback<Node>,
+    /// Called when the collapse toggle of the node is clicked.
+    pub on_toggle_collapse: Callback<Node>,
+    /// Whether the node is part of the selection.
+    pub selected: bool,
+    /// Id of the connector a keyboard connection was started from.
+    pub connecting_port: Option<String>,
+    pub on_key_down: Callback<(Node, KeyboardEvent)>,
+    /// Called when a connector is activated with the keyboard.
+    pub on_port_activate: Callback<Connector>,
+    /// Called when a context menu is requested on the node or one of its connectors.
+    pub on_context_menu: Callback<(ContextMenuTarget, MouseEvent)>,
+    /// Called with the field key and new value when a field of the node is edited.
+    pub on_field_change: Callback<(Node, String, FieldValue)>,
+    /// Ids of the inputs with an incoming edge.
+    pub connected_inputs: Vec<StandardId>,
+    /// Called when the inline value of an unconnected input is edited.
+    pub on_input_value_change: Callback<(NodeInput, FieldValue)>,
+    /// Whether the node is emphasized by the current highlight.
+    pub highlighted: bool,
+    /// Whether the node is outside the current highlight.
+    pub dimmed: bool,
+    pub on_pointer_enter: Callback<Node>,
+    pub on_pointer_leave: Callback<Node>,
+}
+
+/// Whether the keyboard event activates a button (`Enter` or `Space`).
+fn is_activation_key(e: &KeyboardEvent) -> bool {
+    matches!(e.key().as_str(), "Enter" | " ")
+}
+
+/// Replace the browser context menu with our own for `target`.
+fn context_menu_callback(
+    on_context_menu: &Callback<(ContextMenuTarget, MouseEvent)>,
+    target: ContextMenuTarget,
+) -> Callback<MouseEvent> {
+    let on_context_menu = on_context_menu.clone();
+    Callback::from(move |e: MouseEvent| {
+        e.prevent_default();
+        e.stop_propagation();
+        on_context_menu.emit((target.clone(), e))
+    })
+}
+
+/// Single visible anchor for the merged connectors of one side of a collapsed node.
+fn render_anchor(highlighted: bool, ports: Html) -> Html {
+    html! {
+        <span class={classes!("relative", "w-3", "h-3")}>
+            <span class={classes!(
+                "absolute",
+                "inset-0",
+                "border-2",
+                "rounded-full",
+                "pointer-events-none",
+                if highlighted {
+                    classes!("bg-sky-400", "border-sky-100", "scale-150")
+                } else {
+                    classes!("bg-neutral-600", "border-neutral-100")
+                },
+            )} />
+            {ports}
+        </span>
+    }
+}
+
+/// Badge showing the execution state of a node, nothing while idle.
+fn render_status(status: &NodeStatus) -> Html {
+    let (text, color, title) = match status {
+        NodeStatus::Idle => return html! {},
+        NodeStatus::Running => ("running", "text-sky-300", None),
+        NodeStatus::Done => ("done", "text-emerald-300", None),
+        NodeStatus::Error(message) => ("error", "text-red-400", Some(message.clone())),
+    };
+    html! {
+        <span
+            role="status"
+            {title}
+            class={classes!("absolute", "top-0", "right-2", "text-xs", color)}
+        >
+            {text}
+        </span>
+    }
+}
+
+/// Wrap value editors so that editing them does not drag the
+/// node or trigger workspace shortcuts.
+fn render_editors(class: Classes, editors: Html) -> Html {
+    html! {
+        <div
+            onpointerdown={Callback::from(|e: PointerEvent| e.stop_propagation())}
+            onkeydown={Callback::from(|e: KeyboardEvent| e.stop_propagation())}
+            ondblclick={Callback::from(|e: MouseEvent| e.stop_propagation())}
+            // clipboard shortcuts apply to the text, not the selected nodes
+            oncopy={Callback::from(|e: Event| e.stop_propagation())}
+            oncut={Callback::from(|e: Event| e.stop_propagation())}
+            onpaste={Callback::from(|e: Event| e.stop_propagation())}
+            class={classes!(class, "select-text")}
+        >
+            {editors}
+        </div>
+    }
+}
+
+/// Place a connector on its side of the node with its label towards the node body.
+fn render_placed_port(
+    side: PortSide,
+    offset: Option<StandardUnit>,
+    label: &Option<String>,
+    editor: Html,
+    port: Html,
+) -> Html {
+    let style = offset.map(|offset| {
+        if side.is_vertical() {
+            format!("top: {}px;", offset)
+        } else {
+            format!("left: {}px;", offset)
+        }
+    });
+    html! {
+        <span
+            style={style}
+            class={classes!(
+                "flex",
+                "items-center",
+                "gap-1",
+                match side {
+                    PortSide::Left => classes!("flex-row", "my-1"),
+                    PortSide::Right => classes!("flex-row-reverse", "my-1"),
+                    PortSide::Top => classes!("flex-col", "mx-1"),
+                    PortSide::Bottom => classes!("flex-col-reverse", "mx-1"),
+                },
+                // explicitly placed connectors leave the even spread
+                offset.map(|_| match side {
+                    PortSide::Left => classes!("absolute", "left-0"),
+                    PortSide::Right => classes!("absolute", "right-0"),
+                    PortSide::Top => classes!("absolute", "top-0"),
+                    PortSide::Bottom => classes!("absolute", "bottom-0"),
+                }),
+            )}
+        >
+            {port}
+            if let Some(label) = label {
+                <span class={classes!(
+                    "text-xs",
+                    "text-neutral-200",
+                    "whitespace-nowrap",
+                    "pointer-events-none",
+                )}
+                >
+                    {label.clone()}
+                </span>
+            }
+            {editor}
+        </span>
+    }
 }
 
 #[function_component(RenderNode)]
 pub fn render_node(
     RenderNodeProps {
         node,
-        on_mouse_down,
-        on_mouse_up,
+        highlighted_port,
+        on_pointer_down,
         on_click,
-        on_input_mouse_down,
-        on_input_mouse_up,
-        on_output_mouse_down,
-        on_output_mouse_up,
+        on_double_click,
+        on_toggle_collapse,
+        on_input_pointer_down,
+        on_output_pointer_down,
+        selected,
+        connecting_port,
+        on_key_down,
+        on_port_activate,
+        on_context_menu,
+        on_field_change,
+        connected_inputs,
+        on_input_value_change,
+        highlighted,
+        dimmed,
+        on_pointer_enter,
+        on_pointer_leave,
     }: &RenderNodeProps,
 ) -> Html {
     // log::info!("render_node: {}", node.id);
-    let render_inputs = node
+    let inputs = node
         .inputs
         .iter()
         .map(|input| {
-            let handle_mouse_down = {
-                let on_input_mouse_down = on_input_mouse_down.clone();
-                let input = input.clone();
-                Callback::from(move |e: MouseEvent| {
-                    e.stop_propagation();
-                    on_input_mouse_down.emit(input.clone())
+            let highlighted = highlighted_port.as_ref() == Some(&input.id);
+            let connecting = connecting_port.as_ref() == Some(&input.id);
+            let handle_key_down = {
+                let on_port_activate = on_port_activate.clone();
+                let id = input.id.clone();
+                Callback::from(move |e: KeyboardEvent| {
+                    if is_activation_key(&e) {
+                        e.prevent_default();
+                        e.stop_propagation();
+                        on_port_activate.emit(Connector::Input(id.clone()))
+                    }
                 })
             };
-            let handle_mouse_up = {
-                let on_input_mouse_up = on_input_mouse_up.clone();
+            let handle_pointer_down = {
+                let on_input_pointer_down = on_input_pointer_down.clone();
                 let input = input.clone();
-                Callback::from(move |e: MouseEvent| {
+                Callback::from(move |e: PointerEvent| {
+                    // let secondary pointers bubble up for multi-touch gestures
+                    if !e.is_primary() {
+                        return;
+                    }
                     e.stop_propagation();
-                    on_input_mouse_up.emit(input.clone())
+                    capture_pointer(&e);
+                    on_input_pointer_down.emit((input.clone(), e))
                 })
             };
-            html! {
+            let port = html! {
                 <span
                     key={input.id.clone()}
                     ref={input.reference.clone()}
-                    onmousedown={handle_mouse_down}
-                    onmouseup={handle_mouse_up}
+                    tabindex="0"
+                    role="button"
+                    aria-label={connector_label(slice::from_ref(node), &Connector::Input(input.id.clone()))}
+                    aria-pressed={connecting.to_string()}
+                    onpointerdown={handle_pointer_down}
+                    onkeydown={handle_key_down}
+                    oncontextmenu={context_menu_callback(
+                        on_context_menu,
+                        ContextMenuTarget::Port(Connector::Input(input.id.clone())),
+                    )}
                     class={classes!(
-                        "bg-neutral-600",
                         "border-2",
-                        "border-neutral-100",
                         "w-3",
                         "h-3",
                         "rounded-full",
-                        "my-1",
+                        // collapsed ports are stacked invisibly on the anchor
+                        node.collapsed.then(|| classes!("absolute", "inset-0", "opacity-0")),
+                        "focus:outline-none",
+                        "focus:ring-2",
+                        "focus:ring-sky-200",
+                        if highlighted || connecting {
+                            classes!("bg-sky-400", "border-sky-100", "scale-150")
+                        } else {
+                            classes!("bg-neutral-600", "border-neutral-100")
+                        },
                         )}
                 />
-            }
+            };
+            let port = if node.collapsed {
+                port
+            } else {
+                // unconnected inputs can be given a value inline
+                let editor = match input.widget {
+                    Some(ref widget) => {
+                        let on_change = {
+                            let on_input_value_change = on_input_value_change.clone();
+                            let input = input.clone();
+                            Callback::from(move |value: FieldValue| {
+                                on_input_value_change.emit((input.clone(), value))
+                            })
+                        };
+                        let field = NodeField {
+                            key: input.id.clone(),
+                            label: connector_label(slice::from_ref(node), &Connector::Input(input.id.clone())),
+                            kind: widget.clone(),
+                        };
+                        let connected = connected_inputs.contains(&input.id);
+                        render_editors(
+                            // keep the layout so connectors do not move when hidden
+                            classes!(connected.then(|| "invisible")),
+                            html! {
+                                <RenderNodeField
+                                    {field}
+                                    value={input.inline_value().unwrap_or_else(|| widget.default_value())}
+                                    inline={true}
+                                    {on_change}
+                                />
+                            },
+                        )
+                    }
+                    None => html! {},
+                };
+                render_placed_port(input.side, input.offset, &input.label, editor, port)
+            };
+            (input.side, highlighted || connecting, port)
         })
-        .collect::<Html>();
-    let render_outputs = node
+        .collect::<Vec<_>>();
+    let outputs = node
         .outputs
         .iter()
         .map(|output| {
-            let handle_mouse_down = {
-                let on_output_mouse_down = on_output_mouse_down.clone();
-                let output = output.clone();
-                Callback::from(move |e: MouseEvent| {
-                    e.stop_propagation();
-                    on_output_mouse_down.emit(output.clone())
+            let highlighted = highlighted_port.as_ref() == Some(&output.id);
+            let connecting = connecting_port.as_ref() == Some(&output.id);
+            let handle_key_down = {
+                let on_port_activate = on_port_activate.clone();
+                let id = output.id.clone();
+                Callback::from(move |e: KeyboardEvent| {
+                    if is_activation_key(&e) {
+                        e.prevent_default();
+                        e.stop_propagation();
+                        on_port_activate.emit(Connector::Output(id.clone()))
+                    }
                 })
             };
-            let handle_mouse_up = {
-                let on_output_mouse_up = on_output_mouse_up.clone();
+            let handle_pointer_down = {
+                let on_output_pointer_down = on_output_pointer_down.clone();
                 let output = output.clone();
-                Callback::from(move |e: MouseEvent| {
+                Callback::from(move |e: PointerEvent| {
+                    // let secondary pointers bubble up for multi-touch gestures
+                    if !e.is_primary() {
+                        return;
+                    }
                     e.stop_propagation();
-                    on_output_mouse_up.emit(output.clone())
+                    capture_pointer(&e);
+                    on_output_pointer_down.emit((output.clone(), e))
                 })
             };
-            html! {
+            let port = html! {
                 <span
                     key={output.id.clone()}
                     ref={output.reference.clone()}
-                    onmousedown={handle_mouse_down}
-                    onmouseup={handle_mouse_up}
+                    tabindex="0"
+                    role="button"
+                    aria-label={connector_label(slice::from_ref(node), &Connector::Output(output.id.clone()))}
+                    aria-pressed={connecting.to_string()}
+                    onpointerdown={handle_pointer_down}
+                    onkeydown={handle_key_down}
+                    oncontextmenu={context_menu_callback(
+                        on_context_menu,
+                        ContextMenuTarget::Port(Connector::Output(output.id.clone())),
+                    )}
                     class={classes!(
-                        "bg-neutral-600",
                         "border-2",
-                        "border-neutral-100",
                         "w-3",
                         "h-3",
                         "rounded-full",
-                        "my-1",
+                        // collapsed ports are stacked invisibly on the anchor
+                        node.collapsed.then(|| classes!("absolute", "inset-0", "opacity-0")),
+                        "focus:outline-none",
+                        "focus:ring-2",
+                        "focus:ring-sky-200",
+                        if highlighted || connecting {
+                            classes!("bg-sky-400", "border-sky-100", "scale-150")
+                        } else {
+                            classes!("bg-neutral-600", "border-neutral-100")
+                        },
                         )}
                 />
-            }
+            };
+            let port = if node.collapsed {
+                port
+            } else {
+                render_placed_port(output.side, output.offset, &output.label, html! {}, port)
+            };
+            (output.side, highlighted || connecting, port)
         })
-        .collect::<Html>();
+        .collect::<Vec<_>>();
 
-    let handle_mouse_down = {
-        let on_mouse_down = on_mouse_down.clone();
+    let handle_pointer_down = {
+        let on_pointer_down = on_pointer_down.clone();
         let node = node.clone();
-        Callback::from(move |_| on_mouse_down.emit(node.clone()))
+        Callback::from(move |e: PointerEvent| {
+            // let secondary pointers bubble up for multi-touch gestures
+            if !e.is_primary() {
+                return;
+            }
+            e.stop_propagation();
+            capture_pointer(&e);
+            on_pointer_down.emit(node.clone())
+        })
     };
-    let handle_mouse_up = {
-        let on_mouse_up = on_mouse_up.clone();
+    let handle_click = {
+        let on_click = on_click.clone();
+        let node = node.clone();
+        Callback::from(move |e: MouseEvent| on_click.emit((node.clone(), e)))
+    };
+    let handle_toggle_collapse = {
+        let on_toggle_collapse = on_toggle_collapse.clone();
         let node = node.clone();
         Callback::from(move |e: MouseEvent| {
             e.stop_propagation();
-            on_mouse_up.emit(node.clone())
+            on_toggle_collapse.emit(node.clone())
         })
     };
-    let handle_click = {
-        let on_click = on_click.clone();
+    let handle_double_click = {
+        let on_double_click = on_double_click.clone();
+        let node = node.clone();
+        Callback::from(move |_: MouseEvent| on_double_click.emit(node.clone()))
+    };
+    let handle_key_down = {
+        let on_key_down = on_key_down.clone();
+        let node = node.clone();
+        Callback::from(move |e: KeyboardEvent| on_key_down.emit((node.clone(), e)))
+    };
+    let handle_pointer_enter = {
+        let on_pointer_enter = on_pointer_enter.clone();
+        let node = node.clone();
+        Callback::from(move |_: PointerEvent| on_pointer_enter.emit(node.clone()))
+    };
+    let handle_pointer_leave = {
+        let on_pointer_leave = on_pointer_leave.clone();
         let node = node.clone();
-        Callback::from(move |_| on_click.emit(node.clone()))
+        Callback::from(move |_: PointerEvent| on_pointer_leave.emit(node.clone()))
+    };
+
+    let ports = inputs.into_iter().chain(outputs).collect::<Vec<_>>();
+    let render_side = |side: PortSide| {
+        let side_ports = ports
+            .iter()
+            .filter(|(port_side, ..)| *port_side == side)
+            .collect::<Vec<_>>();
+        let render_ports = side_ports
+            .iter()
+            .map(|(_, _, port)| port.clone())
+            .collect::<Html>();
+        // ports of collapsed nodes are merged into one anchor per side
+        let render_ports = if node.collapsed && !side_ports.is_empty() {
+            let highlighted = side_ports.iter().any(|(_, active, _)| *active);
+            render_anchor(highlighted, render_ports)
+        } else {
+            render_ports
+        };
+        html! {
+            <span class={classes!(
+                "absolute",
+                "flex",
+                "justify-center",
+                match side {
+                    PortSide::Left => classes!("flex-col", "h-full", "-left-2"),
+                    PortSide::Right => classes!("flex-col", "h-full", "-right-2"),
+                    PortSide::Top => classes!("flex-row", "w-full", "left-0", "-top-2"),
+                    PortSide::Bottom => classes!("flex-row", "w-full", "left-0", "-bottom-2"),
+                },
+            )}
+            >
+                {render_ports}
+            </span>
+        }
+    };
+
+    let render_fields = if node.collapsed || node.fields.is_empty() {
+        html! {}
+    } else {
+        let render_fields = node
+            .fields
+            .iter()
+            .map(|field| {
+                let on_change = {
+                    let on_field_change = on_field_change.clone();
+                    let node = node.clone();
+                    let key = field.key.clone();
+                    Callback::from(move |value: FieldValue| {
+                        on_field_change.emit((node.clone(), key.clone(), value))
+                    })
+                };
+                html! {
+                    <RenderNodeField
+                        key={field.key.clone()}
+                        field={field.clone()}
+                        value={node.field_value(field)}
+                        {on_change}
+                    />
+                }
+            })
+            .collect::<Html>();
+        render_editors(
+            classes!("w-full", "px-3", "flex", "flex-col", "gap-1"),
+            render_fields,
+        )
+    };
+
+    let render_status = render_status(&node.status);
+
+    let render_progress = match (&node.status, node.progress) {
+        (NodeStatus::Running, Some(progress)) => html! {
+            <span
+                role="progressbar"
+                aria-label={format!("progress of {}", node.title)}
+                aria-valuemin="0"
+                aria-valuemax="100"
+                aria-valuenow={progress.round().to_string()}
+                style={format!("width: {}%;", progress)}
+                class={classes!("absolute", "bottom-0", "left-0", "h-1", "rounded-b-lg", "bg-sky-400")}
+            />
+        },
+        _ => html! {},
     };
 
     let bg_color = Rgb::from_hex_str(&node.color).unwrap_or(Rgb::new(100., 0., 0., Some(1.0)));
@@ -136,12 +517,28 @@ pub fn render_node(
     html! {
         <div
             key={node.id.clone()}
-            onmousedown={handle_mouse_down}
-            onmouseup={handle_mouse_up}
+            tabindex="0"
+            role="group"
+            aria-roledescription={if node.subflow.is_some() { "subflow node" } else { "node" }}
+            aria-label={format!(
+                "{}{}{}",
+                node.title,
+                if node.collapsed { " (collapsed)" } else { "" },
+                if *selected { " (selected)" } else { "" },
+            )}
+            onpointerdown={handle_pointer_down}
+            onpointerenter={handle_pointer_enter}
+            onpointerleave={handle_pointer_leave}
             onclick={handle_click}
+            ondblclick={handle_double_click}
+            onkeydown={handle_key_down}
+            oncontextmenu={context_menu_callback(
+                on_context_menu,
+                ContextMenuTarget::Node(node.id.clone()),
+            )}
             style={format!("width: {width}px; height: {height}px; left: {left}px; top: {top}px; border-color: {border_color}; background: {background};",
                 width = NODE_WIDTH,
-                height = NODE_HEIGHT,
+                height = node.height(),
                 left = node.x,
                 top = node.y,
                 border_color = node.color,
@@ -152,44 +549,57 @@ pub fn render_node(
                 "border-2",
                 "rounded-lg",
                 "flex",
+                "focus:outline-none",
+                "focus:ring-2",
+                "focus:ring-sky-200",
+                selected.then(|| classes!("ring-2", "ring-sky-400")),
+                (*highlighted && !*selected).then(|| classes!("ring-2", "ring-amber-300")),
+                dimmed.then(|| classes!("opacity-40")),
+                // stacked look for subflow nodes
+                node.subflow.is_some().then(|| classes!("border-double", "border-4")),
             )}
         >
             <div class={classes!(
                 "w-full",
                 "flex",
+                "flex-col",
                 "items-center",
                 "justify-center",
                 "select-none",
                 "relative",
             )}
             >
-                // inputs
-                <span class={classes!(
-                    "absolute",
-                    "flex",
-                    "flex-col",
-                    "justify-center",
-                    "h-full",
-                    "-left-2",
-                    )}
-                >
-                   {render_inputs}
-                </span>
-                // outputs
-                <span class={classes!(
-                    "absolute",
-                    "flex",
-                    "flex-col",
-                    "justify-center",
-                    "h-full",
-                    "-right-2",
+                {render_side(PortSide::Left)}
+                {render_side(PortSide::Right)}
+                {render_side(PortSide::Top)}
+                {render_side(PortSide::Bottom)}
+                <button
+                    aria-label={if node.collapsed { "expand" } else { "collapse" }}
+                    aria-expanded={(!node.collapsed).to_string()}
+                    // the toggle must not start a node drag
+                    onpointerdown={Callback::from(|e: PointerEvent| e.stop_propagation())}
+                    onclick={handle_toggle_collapse}
+                    class={classes!(
+                        "absolute",
+                        "top-0",
+                        "left-2",
+                        "focus:outline-none",
+                        "focus:ring-2",
+                        "focus:ring-sky-200",
                     )}
                 >
-                   {render_outputs}
+                    {if node.collapsed { "▸" } else { "▾" }}
+                </button>
+                <span class={classes!("text-center")}>
+                    {format!("{}", node.title)}
+                    if !node.collapsed {
+                        <br />
+                        {format!("({},{})", node.x, node.y)}
+                    }
                 </span>
-                {format!("{}", node.title)}
-                <br />
-                {format!("({},{})", node.x, node.y)}
+                {render_fields}
+                {render_status}
+                {render_progress}
             </div>
         </div>
     }
@@ -0,0 +1,163 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use super::models::{FieldKind, FieldValue, NodeField};
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct RenderNodeFieldProps {
+    pub field: NodeField,
+    pub value: FieldValue,
+    /// Called with the new value when the control is edited.
+    pub on_change: Callback<FieldValue>,
+    /// Render only the control, e.g. next to a connector that has its own label.
+    #[prop_or_default]
+    pub inline: bool,
+}
+
+/// Clamp `value` to the optional range of a number field.
+fn clamp(value: f64, min: Option<f64>, max: Option<f64>) -> f64 {
+    value
+        .max(min.unwrap_or(f64::MIN))
+        .min(max.unwrap_or(f64::MAX))
+}
+
+#[function_component(RenderNodeField)]
+pub fn render_node_field(
+    RenderNodeFieldProps {
+        field,
+        value,
+        on_change,
+        inline,
+    }: &RenderNodeFieldProps,
+) -> Html {
+    let control_classes = classes!(
+        "w-20",
+        "min-w-0",
+        "px-1",
+        "rounded-sm",
+        "bg-neutral-700",
+        "text-neutral-50",
+        "focus:outline-none",
+        "focus:ring-2",
+        "focus:ring-sky-200",
+    );
+    let text = match value {
+        FieldValue::Text(text) => text.clone(),
+        FieldValue::Number(number) => number.to_string(),
+        FieldValue::Bool(checked) => checked.to_string(),
+    };
+    let control = match &field.kind {
+        FieldKind::Text | FieldKind::Color => {
+            let handle_change = {
+                let on_change = on_change.clone();
+                Callback::from(move |e: Event| {
+                    if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                        on_change.emit(FieldValue::Text(input.value()))
+                    }
+                })
+            };
+            html! {
+                <input
+                    type={if field.kind == FieldKind::Color { "color" } else { "text" }}
+                    aria-label={field.label.clone()}
+                    value={text}
+                    onchange={handle_change}
+                    class={control_classes}
+                />
+            }
+        }
+        FieldKind::Number { min, max, step } => {
+            let handle_change = {
+                let on_change = on_change.clone();
+                let (min, max) = (*min, *max);
+                Callback::from(move |e: Event| {
+                    if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                        // ignore input that is not a number
+                        if let Ok(number) = input.value().parse::<f64>() {
+                            let number = clamp(number, min, max);
+                            input.set_value(&number.to_string());
+                            on_change.emit(FieldValue::Number(number))
+                        }
+                    }
+                })
+            };
+            html! {
+                <input
+                    type="number"
+                    aria-label={field.label.clone()}
+                    min={min.map(|min| min.to_string())}
+                    max={max.map(|max| max.to_string())}
+                    step={step.map(|step| step.to_string()).unwrap_or_else(|| "any".to_string())}
+                    value={text}
+                    onchange={handle_change}
+                    class={control_classes}
+                />
+            }
+        }
+        FieldKind::Checkbox => {
+            let handle_change = {
+                let on_change = on_change.clone();
+                Callback::from(move |e: Event| {
+                    if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                        on_change.emit(FieldValue::Bool(input.checked()))
+                    }
+                })
+            };
+            html! {
+                <input
+                    type="checkbox"
+                    aria-label={field.label.clone()}
+                    checked={*value == FieldValue::Bool(true)}
+                    onchange={handle_change}
+                    class={classes!("focus:outline-none", "focus:ring-2", "focus:ring-sky-200")}
+                />
+            }
+        }
+        FieldKind::Select { options } => {
+            let handle_change = {
+                let on_change = on_change.clone();
+                Callback::from(move |e: Event| {
+                    if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                        on_change.emit(FieldValue::Text(select.value()))
+                    }
+                })
+            };
+            let render_options = options
+                .iter()
+                .map(|option| {
+                    html! {
+                        <option value={option.clone()} selected={*option == text}>
+                            {option.clone()}
+                        </option>
+                    }
+                })
+                .collect::<Html>();
+            html! {
+                <select
+                    aria-label={field.label.clone()}
+                    onchange={handle_change}
+                    class={control_classes}
+                >
+                    {render_options}
+                </select>
+            }
+        }
+    };
+
+    if *inline {
+        return control;
+    }
+    html! {
+        <label class={classes!(
+            "flex",
+            "items-center",
+            "justify-between",
+            "gap-1",
+            "text-xs",
+        )}
+        >
+            <span class={classes!("truncate")}>{field.label.clone()}</span>
+            {control}
+        </label>
+    }
+}
@@ -1,19 +1,33 @@
 use yew::prelude::*;
 
-use crate::store::{
-    Connector, InteractionMode, NewEdgeDragActivateCmd, NewEdgeDragDeactivateCmd, WorkspaceAction,
-    WorkspaceStore,
+use crate::{
+    components::{context_menu::models::ContextMenuTarget, edge::models::Edge},
+    constants::KEYBOARD_MOVE_STEP,
+    graph::{GraphIndex, Highlight},
+    store::{
+        Connector, ContextMenuOpenCmd, EdgeEnd, EdgeUpdateActivateCmd, EdgeUpdateMode,
+        InputValueChangeCmd, InteractionMode, NewEdgeDragActivateCmd, NewEdgeDragMode,
+        NodeFieldChangeCmd, NodeSelectCmd, NodesMoveCmd, WorkspaceAction, WorkspaceStore,
+    },
+    types::standard_id::StandardId,
 };
 
 use super::{
-    models::{Node, NodeInput, NodeOutput},
+    models::{FieldValue, Node, NodeInput, NodeOutput},
     render_node::RenderNode,
 };
 
 #[derive(Clone, Properties, PartialEq)]
 pub struct RenderNodeListProps {
     pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    /// Connections of the nodes, see `WorkspaceStore::graph`.
+    pub graph: GraphIndex,
     pub interaction_mode: InteractionMode,
+    /// Ids of the selected nodes.
+    pub selection: Vec<StandardId>,
+    /// Nodes to emphasize, the others are dimmed.
+    pub highlight: Option<Highlight>,
     pub dispatcher: UseReducerDispatcher<WorkspaceStore>,
 }
 
@@ -21,11 +35,15 @@ pub struct RenderNodeListProps {
 pub fn render_node_list(
     RenderNodeListProps {
         nodes,
+        edges,
+        graph,
         interaction_mode,
+        selection,
+        highlight,
         dispatcher,
     }: &RenderNodeListProps,
 ) -> Html {
-    let on_node_mouse_down = {
+    let on_node_pointer_down = {
         let dispatcher = dispatcher.clone();
         use_callback(
             move |node: Node, dispatcher| {
@@ -34,113 +52,253 @@ pub fn render_node_list(
             dispatcher,
         )
     };
-    let on_node_mouse_up = {
+    let on_node_click = {
         let dispatcher = dispatcher.clone();
-        let interaction_mode = interaction_mode.clone();
         use_callback(
-            move |node: Node, (dispatcher, interaction_mode)| {
-                match interaction_mode {
-                    InteractionMode::None => {
-                        // dispatch(WorkspaceAction::DragNode(DragNodeCmd { x, y }))
-                    }
-                    InteractionMode::NodeDrag(_) => {
-                        dispatcher.dispatch(WorkspaceAction::NodeDragDeactivate)
-                    }
-                    InteractionMode::NewEdgeDrag(_) => dispatcher.dispatch(
-                        WorkspaceAction::NewEdgeDragDeactivate(NewEdgeDragDeactivateCmd {
-                            to_reference: None,
-                            to_connector: None,
-                        }),
-                    ),
+            move |(node, e): (Node, MouseEvent), dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::NodeSelect(NodeSelectCmd {
+                    id: node.id,
+                    additive: e.shift_key() || e.ctrl_key() || e.meta_key(),
+                }))
+            },
+            dispatcher,
+        )
+    };
+    let on_node_double_click = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |node: Node, dispatcher| {
+                // open the inner graph of subflow nodes
+                if node.subflow.is_some() {
+                    dispatcher.dispatch(WorkspaceAction::SubflowEnter(node.id))
                 }
             },
-            (dispatcher, interaction_mode),
+            dispatcher,
         )
     };
-    let on_node_click = {
+    let on_node_toggle_collapse = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |node: Node, dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::NodeToggleCollapse(node.id))
+            },
+            dispatcher,
+        )
+    };
+    let on_node_field_change = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |(node, key, value): (Node, String, FieldValue), dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::NodeFieldChange(NodeFieldChangeCmd {
+                    id: node.id,
+                    key,
+                    value,
+                }))
+            },
+            dispatcher,
+        )
+    };
+    let on_input_value_change = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |(input, value): (NodeInput, FieldValue), dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::InputValueChange(InputValueChangeCmd {
+                    id: input.id,
+                    value,
+                }))
+            },
+            dispatcher,
+        )
+    };
+    let on_node_key_down = {
         let dispatcher = dispatcher.clone();
+        let selection = selection.clone();
         use_callback(
-            move |node: Node, _| {
-                // if node.is_active {
-                //     store.dispatch(NodesAction::Deactivate(node.id));
-                // } else {
-                //     store.dispatch(NodesAction::Activate(node.id));
-                // }
+            move |(node, e): (Node, KeyboardEvent), (dispatcher, selection)| {
+                if e.key() == "Enter" && node.subflow.is_some() {
+                    e.prevent_default();
+                    dispatcher.dispatch(WorkspaceAction::SubflowEnter(node.id));
+                    return;
+                }
+                // hold shift for fine grained moves
+                let step = if e.shift_key() {
+                    KEYBOARD_MOVE_STEP / 10.
+                } else {
+                    KEYBOARD_MOVE_STEP
+                };
+                let (dx, dy) = match e.key().as_str() {
+                    "ArrowLeft" => (-step, 0.),
+                    "ArrowRight" => (step, 0.),
+                    "ArrowUp" => (0., -step),
+                    "ArrowDown" => (0., step),
+                    _ => return,
+                };
+                e.prevent_default();
+                // move the whole selection when the focused node is part of it
+                let ids = if selection.contains(&node.id) {
+                    selection.clone()
+                } else {
+                    vec![node.id]
+                };
+                dispatcher.dispatch(WorkspaceAction::NodesMove(NodesMoveCmd { ids, dx, dy }))
             },
-            (),
+            (dispatcher, selection),
         )
     };
-    let on_node_input_mouse_down = {
+    let on_node_pointer_enter = {
         let dispatcher = dispatcher.clone();
         use_callback(
-            move |input: NodeInput, dispatcher| {
-                dispatcher.dispatch(WorkspaceAction::NewEdgeDragActivate(
-                    NewEdgeDragActivateCmd {
-                        from_reference: input.reference,
-                        from_connector: Connector::Input(input.id),
-                    },
-                ))
+            move |node: Node, dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::NodeHoverActivate(node.id))
             },
             dispatcher,
         )
     };
-    let on_node_input_mouse_up = {
+    let on_node_pointer_leave = {
         let dispatcher = dispatcher.clone();
         use_callback(
-            move |input: NodeInput, dispatcher| {
-                dispatcher.dispatch(WorkspaceAction::NewEdgeDragDeactivate(
-                    NewEdgeDragDeactivateCmd {
-                        to_reference: Some(input.reference),
-                        to_connector: Some(Connector::Input(input.id)),
-                    },
-                ))
+            move |node: Node, dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::NodeHoverDeactivate(node.id))
             },
             dispatcher,
         )
     };
-    let on_node_output_mouse_down = {
+    let on_port_activate = {
         let dispatcher = dispatcher.clone();
         use_callback(
-            move |output: NodeOutput, dispatcher| {
-                dispatcher.dispatch(WorkspaceAction::NewEdgeDragActivate(
-                    NewEdgeDragActivateCmd {
-                        from_reference: output.reference,
-                        from_connector: Connector::Output(output.id),
-                    },
-                ))
+            move |connector: Connector, dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::KeyboardConnect(connector))
             },
             dispatcher,
         )
     };
-    let on_node_output_mouse_up = {
+    let on_context_menu = {
         let dispatcher = dispatcher.clone();
         use_callback(
-            move |output: NodeOutput, dispatcher| {
-                dispatcher.dispatch(WorkspaceAction::NewEdgeDragDeactivate(
-                    NewEdgeDragDeactivateCmd {
-                        to_reference: Some(output.reference),
-                        to_connector: Some(Connector::Output(output.id)),
-                    },
-                ))
+            move |(target, e): (ContextMenuTarget, MouseEvent), dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::ContextMenuOpen(ContextMenuOpenCmd {
+                    target,
+                    x: e.page_x().into(),
+                    y: e.page_y().into(),
+                }))
             },
             dispatcher,
         )
     };
+    let on_node_input_pointer_down = {
+        let dispatcher = dispatcher.clone();
+        let edges = edges.clone();
+        use_callback(
+            move |(input, e): (NodeInput, PointerEvent), (dispatcher, edges)| {
+                // shift + drag on a connected input detaches its edge
+                let connected_edge = edges
+                    .iter()
+                    .rev()
+                    .find(|edge| edge.to_input.as_ref() == Some(&input.id));
+                match connected_edge {
+                    Some(edge) if e.shift_key() => dispatcher.dispatch(
+                        WorkspaceAction::EdgeUpdateActivate(EdgeUpdateActivateCmd {
+                            edge_id: edge.id.clone(),
+                            end: EdgeEnd::To,
+                        }),
+                    ),
+                    _ => dispatcher.dispatch(WorkspaceAction::NewEdgeDragActivate(
+                        NewEdgeDragActivateCmd {
+                            from_reference: input.reference,
+                            from_connector: Connector::Input(input.id),
+                        },
+                    )),
+                }
+            },
+            (dispatcher, edges),
+        )
+    };
+    let on_node_output_pointer_down = {
+        let dispatcher = dispatcher.clone();
+        let edges = edges.clone();
+        use_callback(
+            move |(output, e): (NodeOutput, PointerEvent), (dispatcher, edges)| {
+                // shift + drag on a connected output detaches its latest edge
+                let connected_edge = edges
+                    .iter()
+                    .rev()
+                    .find(|edge| edge.from_output.as_ref() == Some(&output.id));
+                match connected_edge {
+                    Some(edge) if e.shift_key() => dispatcher.dispatch(
+                        WorkspaceAction::EdgeUpdateActivate(EdgeUpdateActivateCmd {
+                            edge_id: edge.id.clone(),
+                            end: EdgeEnd::From,
+                        }),
+                    ),
+                    _ => dispatcher.dispatch(WorkspaceAction::NewEdgeDragActivate(
+                        NewEdgeDragActivateCmd {
+                            from_reference: output.reference,
+                            from_connector: Connector::Output(output.id),
+                        },
+                    )),
+                }
+            },
+            (dispatcher, edges),
+        )
+    };
 
+    // connector to be connected to on release of the dragged edge
+    let snap_target = match interaction_mode {
+        InteractionMode::NewEdgeDrag(NewEdgeDragMode { snap_target, .. })
+        | InteractionMode::EdgeUpdate(EdgeUpdateMode { snap_target, .. }) => snap_target.clone(),
+        _ => None,
+    };
+    // connector a keyboard connection was started from
+    let connecting_port = match interaction_mode {
+        InteractionMode::KeyboardConnect(Connector::Input(id) | Connector::Output(id)) => {
+            Some(id.clone())
+        }
+        _ => None,
+    };
     let render_nodes = {
         nodes
             .iter()
             .map(|node| {
+                let highlighted_port = match snap_target {
+                    Some(Connector::Input(ref id)) if node.inputs.iter().any(|i| i.id == *id) => {
+                        Some(id.clone())
+                    }
+                    Some(Connector::Output(ref id)) if node.outputs.iter().any(|o| o.id == *id) => {
+                        Some(id.clone())
+                    }
+                    _ => None,
+                };
+                let connected_inputs = node
+                    .inputs
+                    .iter()
+                    .filter(|input| !graph.port_edges(&input.id).is_empty())
+                    .map(|input| input.id.clone())
+                    .collect::<Vec<_>>();
+                let highlighted = highlight
+                    .as_ref()
+                    .map(|highlight| highlight.nodes.contains(&node.id));
                 html! {
                     <RenderNode
                         node={node.clone()}
-                        on_mouse_down={on_node_mouse_down.clone()}
-                        on_mouse_up={on_node_mouse_up.clone()}
+                        {highlighted_port}
+                        on_pointer_down={on_node_pointer_down.clone()}
                         on_click={on_node_click.clone()}
-                        on_input_mouse_down={on_node_input_mouse_down.clone()}
-                        on_input_mouse_up={on_node_input_mouse_up.clone()}
-                        on_output_mouse_down={on_node_output_mouse_down.clone()}
-                        on_output_mouse_up={on_node_output_mouse_up.clone()}
+                        on_double_click={on_node_double_click.clone()}
+                        on_toggle_collapse={on_node_toggle_collapse.clone()}
+                        on_input_pointer_down={on_node_input_pointer_down.clone()}
+                        on_output_pointer_down={on_node_output_pointer_down.clone()}
+                        selected={selection.contains(&node.id)}
+                        connecting_port={connecting_port.clone()}
+                        on_key_down={on_node_key_down.clone()}
+                        on_port_activate={on_port_activate.clone()}
+                        on_context_menu={on_context_menu.clone()}
+                        on_field_change={on_node_field_change.clone()}
+                        connected_inputs={connected_inputs}
+                        on_input_value_change={on_input_value_change.clone()}
+                        highlighted={highlighted == Some(true)}
+                        dimmed={highlighted == Some(false)}
+                        on_pointer_enter={on_node_pointer_enter.clone()}
+                        on_pointer_leave={on_node_pointer_leave.clone()}
                     />
                 }
             })
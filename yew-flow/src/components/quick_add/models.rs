@@ -0,0 +1,42 @@
+use crate::{store::Connector, types::standard_unit::StandardUnit};
+
+/// # Quick Add
+///
+/// Open quick-add popup. Position is in flow coordinates and is
+/// where the chosen node gets inserted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickAdd {
+    pub x: StandardUnit,
+    pub y: StandardUnit,
+    /// Connector of the dropped edge the popup was opened from.
+    /// The new node gets connected to it.
+    pub from_connector: Option<Connector>,
+}
+
+/// # Fuzzy Score
+///
+/// Score how well `text` matches `query`. All characters of the query
+/// have to appear in order in the text (case insensitive), consecutive
+/// matches and matches at word starts score higher. Returns `None` when
+/// the text does not match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut last_match: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let index = position + text.get(position..)?.iter().position(|c| *c == q)?;
+        score += 1;
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            // word start
+            score += 2;
+        }
+        if index > 0 && last_match == Some(index - 1) {
+            // consecutive
+            score += 3;
+        }
+        last_match = Some(index);
+        position = index + 1;
+    }
+    Some(score)
+}
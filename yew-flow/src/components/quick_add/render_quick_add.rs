@@ -0,0 +1,194 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::{
+        node::models::NodeTemplate,
+        viewport::models::{ContainerDimensions, ViewportTransform},
+    },
+    constants::{QUICK_ADD_HEIGHT, QUICK_ADD_WIDTH},
+};
+
+use super::models::{fuzzy_score, QuickAdd};
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct RenderQuickAddProps {
+    pub quick_add: QuickAdd,
+    pub templates: Vec<NodeTemplate>,
+    /// Pan/zoom used to place the popup on the screen.
+    pub transform: ViewportTransform,
+    /// Container the popup is clamped to.
+    pub dimensions: ContainerDimensions,
+    pub on_select: Callback<NodeTemplate>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(RenderQuickAdd)]
+pub fn render_quick_add(
+    RenderQuickAddProps {
+        quick_add,
+        templates,
+        transform,
+        dimensions,
+        on_select,
+        on_close,
+    }: &RenderQuickAddProps,
+) -> Html {
+    let input_ref = use_node_ref();
+    let query = use_state(String::new);
+    // index of the highlighted match
+    let active = use_state(|| 0usize);
+
+    {
+        let input_ref = input_ref.clone();
+        use_effect_with_deps(
+            // focus the search input when the popup opens
+            |input_ref| {
+                if let Some(input) = input_ref.cast::<HtmlInputElement>() {
+                    let _ = input.focus();
+                }
+                || ()
+            },
+            input_ref,
+        );
+    }
+
+    // best matches first, ties keep the template order
+    let mut matches = templates
+        .iter()
+        .filter_map(|template| fuzzy_score(&query, &template.title).map(|score| (score, template)))
+        .collect::<Vec<_>>();
+    matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+    let matches = matches
+        .into_iter()
+        .map(|(_, template)| template.clone())
+        .collect::<Vec<_>>();
+
+    let handle_input = {
+        let query = query.clone();
+        let active = active.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                query.set(input.value());
+                active.set(0);
+            }
+        })
+    };
+    let handle_key_down = {
+        let active = active.clone();
+        let matches = matches.clone();
+        let on_select = on_select.clone();
+        let on_close = on_close.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            // keep workspace shortcuts from firing while typing
+            e.stop_propagation();
+            match e.key().as_str() {
+                "ArrowDown" => {
+                    e.prevent_default();
+                    active.set((*active + 1).min(matches.len().saturating_sub(1)));
+                }
+                "ArrowUp" => {
+                    e.prevent_default();
+                    active.set(active.saturating_sub(1));
+                }
+                "Enter" => {
+                    e.prevent_default();
+                    if let Some(template) = matches.get(*active) {
+                        on_select.emit(template.clone())
+                    }
+                }
+                "Escape" => on_close.emit(()),
+                _ => {}
+            }
+        })
+    };
+
+    // flow -> container position, kept inside the container
+    let left = (quick_add.x * transform.zoom + transform.x)
+        .min(dimensions.width - QUICK_ADD_WIDTH)
+        .max(0.);
+    let top = (quick_add.y * transform.zoom + transform.y)
+        .min(dimensions.height - QUICK_ADD_HEIGHT)
+        .max(0.);
+
+    let render_matches = matches
+        .iter()
+        .enumerate()
+        .map(|(index, template)| {
+            let handle_click = {
+                let on_select = on_select.clone();
+                let template = template.clone();
+                Callback::from(move |e: MouseEvent| {
+                    e.stop_propagation();
+                    on_select.emit(template.clone())
+                })
+            };
+            html! {
+                <li
+                    key={template.title.clone()}
+                    role="option"
+                    aria-selected={(index == *active).to_string()}
+                    onclick={handle_click}
+                    class={classes!(
+                        "px-2",
+                        "py-1",
+                        "cursor-pointer",
+                        "hover:bg-neutral-600",
+                        (index == *active).then(|| "bg-neutral-600"),
+                    )}
+                >
+                    <span
+                        style={format!("background: {};", template.color)}
+                        class={classes!("inline-block", "w-2", "h-2", "mr-2", "rounded-full")}
+                    />
+                    {template.title.clone()}
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div
+            // keep interactions with the popup away from the workspace
+            onpointerdown={Callback::from(|e: PointerEvent| e.stop_propagation())}
+            style={format!(
+                "left: {}px; top: {}px; width: {}px; max-height: {}px;",
+                left, top, QUICK_ADD_WIDTH, QUICK_ADD_HEIGHT
+            )}
+            class={classes!(
+                "absolute",
+                "z-10",
+                "flex",
+                "flex-col",
+                "bg-neutral-700",
+                "border",
+                "border-neutral-400",
+                "rounded-sm",
+                "shadow-lg",
+                "select-none",
+            )}
+        >
+            <input
+                ref={input_ref}
+                type="text"
+                role="combobox"
+                aria-label="search nodes"
+                aria-expanded="true"
+                placeholder="Search nodes..."
+                value={(*query).clone()}
+                oninput={handle_input}
+                onkeydown={handle_key_down}
+                class={classes!(
+                    "p-2",
+                    "bg-neutral-800",
+                    "border-b",
+                    "border-neutral-400",
+                    "focus:outline-none",
+                )}
+            />
+            <ul role="listbox" class={classes!("overflow-y-auto")}>
+                {render_matches}
+            </ul>
+        </div>
+    }
+}
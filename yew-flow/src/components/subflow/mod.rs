@@ -0,0 +1 @@
+pub mod render_breadcrumb;
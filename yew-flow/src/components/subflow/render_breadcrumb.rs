@@ -0,0 +1,54 @@
+use yew::prelude::*;
+
+use crate::subflow::SubflowLevel;
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct RenderBreadcrumbProps {
+    /// Parent levels of the open subflow.
+    pub path: Vec<SubflowLevel>,
+    /// Called with the depth to go back to, `0` being the root flow.
+    pub on_navigate: Callback<usize>,
+}
+
+#[function_component(RenderBreadcrumb)]
+pub fn render_breadcrumb(
+    RenderBreadcrumbProps { path, on_navigate }: &RenderBreadcrumbProps,
+) -> Html {
+    // each level links back to the graph it contains
+    let render_levels = std::iter::once("Root".to_string())
+        .chain(path.iter().map(|level| level.title.clone()))
+        .enumerate()
+        .map(|(depth, title)| {
+            if depth == path.len() {
+                // the open level
+                return html! {
+                    <li aria-current="page" class={classes!("text-neutral-50")}>
+                        {title}
+                    </li>
+                };
+            }
+            let handle_click = {
+                let on_navigate = on_navigate.clone();
+                Callback::from(move |_: MouseEvent| on_navigate.emit(depth))
+            };
+            html! {
+                <li class={classes!("after:content-['/']", "after:mx-2")}>
+                    <button
+                        onclick={handle_click}
+                        class={classes!("text-cyan-300", "hover:underline", "focus:outline-none", "focus:ring-2", "focus:ring-sky-200")}
+                    >
+                        {title}
+                    </button>
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <nav aria-label="subflow breadcrumb" class={classes!("pb-1")}>
+            <ol class={classes!("flex", "text-neutral-400")}>
+                {render_levels}
+            </ol>
+        </nav>
+    }
+}
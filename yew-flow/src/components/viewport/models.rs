@@ -1,7 +1,10 @@
 use web_sys::HtmlElement;
 use yew::prelude::*;
 
-use crate::types::standard_unit::StandardUnit;
+use crate::{
+    constants::{MAX_ZOOM, MIN_ZOOM},
+    types::standard_unit::StandardUnit,
+};
 
 /// Used to store container dimensions like
 /// **offsets**, **width**, **height** etc
@@ -24,6 +27,60 @@ impl Default for ContainerDimensions {
     }
 }
 
+/// # Viewport Transform
+///
+/// Pan offset and zoom level of the flow inside the viewport.
+/// Screen position = flow position * zoom + offset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewportTransform {
+    /// Horizontal pan offset in pixels.
+    pub x: StandardUnit,
+    /// Vertical pan offset in pixels.
+    pub y: StandardUnit,
+    /// Zoom/scale factor.
+    pub zoom: StandardUnit,
+}
+
+impl Default for ViewportTransform {
+    fn default() -> Self {
+        Self {
+            x: 0.,
+            y: 0.,
+            zoom: 1.,
+        }
+    }
+}
+
+impl ViewportTransform {
+    /// Zoom by `factor` while keeping the point at (`x`, `y`)
+    /// (relative to the viewport) fixed on the screen.
+    pub fn zoom_at(
+        &self,
+        factor: StandardUnit,
+        x: StandardUnit,
+        y: StandardUnit,
+    ) -> ViewportTransform {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let flow_x = (x - self.x) / self.zoom;
+        let flow_y = (y - self.y) / self.zoom;
+        ViewportTransform {
+            x: x - flow_x * zoom,
+            y: y - flow_y * zoom,
+            zoom,
+        }
+    }
+
+    /// CSS transform string for the transformed pane.
+    pub fn to_css_string(&self) -> String {
+        format!(
+            "translate({x}px, {y}px) scale({zoom})",
+            x = self.x,
+            y = self.y,
+            zoom = self.zoom
+        )
+    }
+}
+
 /// # Yew Flow Viewport Model
 ///
 /// Defines `yew-flow` viewport configuration
@@ -32,6 +89,8 @@ pub struct Viewport {
     /// Viewport element reference
     pub reference: NodeRef,
     pub dimensions: ContainerDimensions,
+    /// Pan/zoom of the flow inside the viewport.
+    pub transform: ViewportTransform,
 }
 
 impl Viewport {
@@ -48,30 +107,65 @@ impl Viewport {
         Self {
             reference,
             dimensions,
+            transform: Default::default(),
         }
     }
 
-    /// Get x position relative to viewport of any element.
+    /// Set the pan/zoom transform of the viewport.
+    pub fn with_transform(mut self, transform: ViewportTransform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Get x position relative to the viewport (not the flow) from an absolute position.
+    pub fn viewport_x_from_abs(self: &Self, abs_x: StandardUnit) -> StandardUnit {
+        abs_x - self.dimensions.offset_left
+    }
+
+    /// Get y position relative to the viewport (not the flow) from an absolute position.
+    pub fn viewport_y_from_abs(self: &Self, abs_y: StandardUnit) -> StandardUnit {
+        abs_y - self.dimensions.offset_top
+    }
+
+    /// Get x position in flow coordinates from an absolute position.
+    pub fn flow_x_from_abs(self: &Self, abs_x: StandardUnit) -> StandardUnit {
+        (self.viewport_x_from_abs(abs_x) - self.transform.x) / self.transform.zoom
+    }
+
+    /// Get y position in flow coordinates from an absolute position.
+    pub fn flow_y_from_abs(self: &Self, abs_y: StandardUnit) -> StandardUnit {
+        (self.viewport_y_from_abs(abs_y) - self.transform.y) / self.transform.zoom
+    }
+
+    /// Get x position relative to the flow of any element.
     pub fn relative_x_pos_from_abs(
         self: &Self,
         abs_x: StandardUnit,
         element_width: Option<StandardUnit>,
     ) -> StandardUnit {
         let element_width = element_width.unwrap_or_default();
-        let x = (abs_x - self.dimensions.offset_left - element_width / 2.)
-            .clamp(0., self.dimensions.width - element_width);
+        let ViewportTransform { x: pan_x, zoom, .. } = self.transform;
+        // keep the element within the visible part of the flow
+        let min_x = -pan_x / zoom;
+        let max_x = ((self.dimensions.width - pan_x) / zoom - element_width).max(min_x);
+        let x = ((self.viewport_x_from_abs(abs_x) - pan_x) / zoom - element_width / 2.)
+            .clamp(min_x, max_x);
         x
     }
 
-    /// Get y position relative to viewport of any element.
+    /// Get y position relative to the flow of any element.
     pub fn relative_y_pos_from_abs(
         self: &Self,
         abs_y: StandardUnit,
         element_height: Option<StandardUnit>,
     ) -> StandardUnit {
         let element_height = element_height.unwrap_or_default();
-        let y = (abs_y - self.dimensions.offset_top - element_height / 2.)
-            .clamp(0., self.dimensions.height - element_height);
+        let ViewportTransform { y: pan_y, zoom, .. } = self.transform;
+        // keep the element within the visible part of the flow
+        let min_y = -pan_y / zoom;
+        let max_y = ((self.dimensions.height - pan_y) / zoom - element_height).max(min_y);
+        let y = ((self.viewport_y_from_abs(abs_y) - pan_y) / zoom - element_height / 2.)
+            .clamp(min_y, max_y);
         y
     }
 }
@@ -4,3 +4,39 @@ use crate::types::standard_unit::StandardUnit;
 pub const NODE_WIDTH: StandardUnit = 150.;
 /// Height of the node.
 pub const NODE_HEIGHT: StandardUnit = 60.;
+/// Height of a collapsed node (header only).
+pub const COLLAPSED_NODE_HEIGHT: StandardUnit = 28.;
+/// Height added to the node per editable field.
+pub const NODE_FIELD_HEIGHT: StandardUnit = 24.;
+/// Size (width/height) of node connectors.
+pub const PORT_SIZE: StandardUnit = 12.;
+/// Default radius within which a dragged edge snaps to a connector.
+pub const CONNECTION_RADIUS: StandardUnit = 24.;
+/// Minimum zoom level of the viewport.
+pub const MIN_ZOOM: StandardUnit = 0.2;
+/// Maximum zoom level of the viewport.
+pub const MAX_ZOOM: StandardUnit = 4.;
+/// Zoom speed of the mouse wheel (per pixel scrolled).
+pub const WHEEL_ZOOM_SPEED: StandardUnit = 0.001;
+/// Distance nodes are moved by with the arrow keys.
+pub const KEYBOARD_MOVE_STEP: StandardUnit = 10.;
+/// Offset of pasted/duplicated nodes from the originals.
+pub const PASTE_OFFSET: StandardUnit = 20.;
+/// Width of the context menu.
+pub const CONTEXT_MENU_WIDTH: StandardUnit = 160.;
+/// Height of a single context menu item.
+pub const CONTEXT_MENU_ITEM_HEIGHT: StandardUnit = 32.;
+/// Width of the quick-add popup.
+pub const QUICK_ADD_WIDTH: StandardUnit = 220.;
+/// Maximum height of the quick-add popup.
+pub const QUICK_ADD_HEIGHT: StandardUnit = 240.;
+/// Height of the group header.
+pub const GROUP_HEADER_HEIGHT: StandardUnit = 28.;
+/// Space between a new group and its nodes.
+pub const GROUP_PADDING: StandardUnit = 20.;
+/// Default width of a new comment.
+pub const COMMENT_WIDTH: StandardUnit = 200.;
+/// Default height of a new comment.
+pub const COMMENT_HEIGHT: StandardUnit = 120.;
+/// Minimum width/height a comment can be resized to.
+pub const COMMENT_MIN_SIZE: StandardUnit = 60.;
@@ -0,0 +1,551 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    rc::Rc,
+};
+
+use crate::{
+    components::node::models::{FieldValue, Node},
+    exec::{AsyncComputeFn, LocalFuture, TaskContext},
+    types::standard_id::StandardId,
+    workspace::YewFlowValues,
+};
+
+/// Compute function of a node kind. Gets the node and the values of its
+/// inputs (in order, `None` when missing) and returns the values of its
+/// outputs (in order).
+pub type ComputeFn = Rc<dyn Fn(&Node, &[Option<FieldValue>]) -> Result<Vec<FieldValue>, String>>;
+
+/// # Compute Registry
+///
+/// Compute functions by node kind.
+#[derive(Clone, Default)]
+pub struct ComputeRegistry {
+    computes: HashMap<String, ComputeFn>,
+    async_computes: HashMap<String, AsyncComputeFn>,
+}
+
+impl ComputeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the compute function of nodes of `kind`.
+    pub fn register<F>(&mut self, kind: impl Into<String>, compute: F) -> &mut Self
+    where
+        F: Fn(&Node, &[Option<FieldValue>]) -> Result<Vec<FieldValue>, String> + 'static,
+    {
+        self.computes.insert(kind.into(), Rc::new(compute));
+        self
+    }
+
+    /// Register an async compute function for nodes of `kind`.
+    /// Async nodes can only be run by the [`Executor`](crate::exec::Executor).
+    pub fn register_async<F>(&mut self, kind: impl Into<String>, compute: F) -> &mut Self
+    where
+        F: Fn(
+                &Node,
+                &[Option<FieldValue>],
+                TaskContext,
+            ) -> LocalFuture<Result<Vec<FieldValue>, String>>
+            + 'static,
+    {
+        self.async_computes.insert(kind.into(), Rc::new(compute));
+        self
+    }
+
+    /// Get the compute function of nodes of `kind`.
+    pub fn get(&self, kind: &str) -> Option<&ComputeFn> {
+        self.computes.get(kind)
+    }
+
+    /// Get the async compute function of nodes of `kind`.
+    pub fn get_async(&self, kind: &str) -> Option<&AsyncComputeFn> {
+        self.async_computes.get(kind)
+    }
+}
+
+/// # Eval Error
+///
+/// Reasons a node could not be evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    /// The node has no kind.
+    MissingKind,
+    /// No compute function is registered for the kind.
+    UnknownKind(String),
+    /// Only an async compute function is registered for the kind.
+    Async(String),
+    /// The compute function failed.
+    Compute(String),
+    /// The compute function returned the wrong number of output values.
+    OutputCount { expected: usize, actual: usize },
+    /// A node feeding the node failed. Takes the id of that node.
+    Upstream(StandardId),
+    /// Nodes of the inner graph of a subflow node failed.
+    Subflow(HashMap<StandardId, EvalError>),
+    /// The node is part of or fed by a cycle.
+    Cycle,
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::MissingKind => write!(f, "node has no kind"),
+            EvalError::UnknownKind(kind) => write!(f, "no compute function for \"{}\"", kind),
+            EvalError::Async(kind) => write!(f, "\"{}\" can only be run asynchronously", kind),
+            EvalError::Compute(message) => write!(f, "{}", message),
+            EvalError::OutputCount { expected, actual } => {
+                write!(f, "expected {} output values, got {}", expected, actual)
+            }
+            EvalError::Upstream(_) => write!(f, "an upstream node failed"),
+            EvalError::Subflow(errors) => write!(f, "{} nodes of the subflow failed", errors.len()),
+            EvalError::Cycle => write!(f, "node is part of a cycle"),
+        }
+    }
+}
+
+/// # Eval Report
+///
+/// Outcome of a single evaluation pass.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvalReport {
+    /// Ids of the (re-)computed nodes in evaluation order.
+    /// Nodes whose result was taken from the cache are not listed.
+    pub evaluated: Vec<StandardId>,
+    /// Errors of all failed nodes by node id.
+    pub errors: HashMap<StandardId, EvalError>,
+}
+
+/// Cached result of a node together with everything it was computed from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CacheEntry {
+    pub kind: Option<String>,
+    pub values: BTreeMap<String, FieldValue>,
+    pub inputs: Vec<Option<FieldValue>>,
+    pub result: Result<Vec<FieldValue>, EvalError>,
+}
+
+impl CacheEntry {
+    /// Whether the entry was computed from the current state of `node` and `inputs`.
+    pub fn matches(&self, node: &Node, inputs: &[Option<FieldValue>]) -> bool {
+        self.kind == node.kind && self.values == node.values && self.inputs == inputs
+    }
+}
+
+/// Check that a compute function returned a value for every output of `node`.
+pub(crate) fn check_output_count(
+    node: &Node,
+    output_values: Vec<FieldValue>,
+) -> Result<Vec<FieldValue>, EvalError> {
+    if output_values.len() != node.outputs.len() {
+        return Err(EvalError::OutputCount {
+            expected: node.outputs.len(),
+            actual: output_values.len(),
+        });
+    }
+    Ok(output_values)
+}
+
+/// Run the compute function registered for the kind of `node`.
+pub(crate) fn compute(
+    registry: &ComputeRegistry,
+    node: &Node,
+    inputs: &[Option<FieldValue>],
+) -> Result<Vec<FieldValue>, EvalError> {
+    let kind = node.kind.as_ref().ok_or(EvalError::MissingKind)?;
+    let compute = match registry.get(kind) {
+        Some(compute) => compute,
+        None if registry.get_async(kind).is_some() => return Err(EvalError::Async(kind.clone())),
+        None => return Err(EvalError::UnknownKind(kind.clone())),
+    };
+    check_output_count(node, compute(node, inputs).map_err(EvalError::Compute)?)
+}
+
+/// # Evaluator
+///
+/// Evaluates a flow by running the compute functions of its nodes in
+/// topological order and passing output values along the edges.
+///
+/// Results are cached per node. A node is only computed again when its kind,
+/// its field values or its input values changed, so after an edit only the
+/// changed node and the downstream nodes whose inputs actually changed run.
+/// Unconnected inputs use their inline value. The inner graph of a subflow
+/// node is evaluated with the values of the subflow node's inputs.
+#[derive(Clone, Default)]
+pub struct Evaluator {
+    registry: ComputeRegistry,
+    cache: HashMap<StandardId, CacheEntry>,
+    /// Evaluators of the inner graphs of subflow nodes by node id.
+    subflows: HashMap<StandardId, Evaluator>,
+    /// Values of all outputs by connector id.
+    outputs: HashMap<StandardId, FieldValue>,
+}
+
+impl Evaluator {
+    pub fn new(registry: ComputeRegistry) -> Self {
+        Self {
+            registry,
+            ..Default::default()
+        }
+    }
+
+    /// Value of the output with `id` after the last evaluation.
+    pub fn output(&self, id: &StandardId) -> Option<&FieldValue> {
+        self.outputs.get(id)
+    }
+
+    /// Values of all outputs after the last evaluation by connector id.
+    pub fn outputs(&self) -> &HashMap<StandardId, FieldValue> {
+        &self.outputs
+    }
+
+    /// Force the node with `id` to be computed again on the next evaluation,
+    /// e.g. when its compute function depends on something outside the flow.
+    pub fn invalidate(&mut self, id: &StandardId) {
+        self.cache.remove(id);
+        self.subflows.remove(id);
+    }
+
+    /// Drop all cached results.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.subflows.clear();
+        self.outputs.clear();
+    }
+
+    /// Evaluate `values`, reusing the cached results of unchanged nodes.
+    pub fn evaluate(&mut self, values: &YewFlowValues) -> EvalReport {
+        self.evaluate_with(values, &HashMap::new())
+    }
+
+    /// Evaluate `values` with `overrides` used as the values of
+    /// unconnected inputs by connector id.
+    fn evaluate_with(
+        &mut self,
+        values: &YewFlowValues,
+        overrides: &HashMap<StandardId, FieldValue>,
+    ) -> EvalReport {
+        let node_of_output = values
+            .nodes
+            .iter()
+            .flat_map(|node| node.outputs.iter().map(move |output| (&output.id, node)))
+            .collect::<HashMap<_, _>>();
+        let source_of_input = values
+            .edges
+            .iter()
+            .filter_map(|edge| edge.to_input.as_ref().zip(edge.from_output.as_ref()))
+            .collect::<HashMap<_, _>>();
+        let (order, cyclic) = values.graph().topological_order();
+
+        let mut report = EvalReport::default();
+        self.outputs.clear();
+        for node in cyclic {
+            report.errors.insert(node.id.clone(), EvalError::Cycle);
+        }
+        for node in order {
+            let mut upstream_error = None;
+            let inputs = node
+                .inputs
+                .iter()
+                .map(|input| match source_of_input.get(&input.id) {
+                    Some(output_id) => {
+                        if let Some(source) = node_of_output.get(output_id) {
+                            if report.errors.contains_key(&source.id) {
+                                upstream_error = Some(source.id.clone());
+                            }
+                        }
+                        self.outputs.get(*output_id).cloned()
+                    }
+                    None => overrides
+                        .get(&input.id)
+                        .cloned()
+                        .or_else(|| input.inline_value()),
+                })
+                .collect::<Vec<_>>();
+
+            let result = match (upstream_error, &node.subflow) {
+                (Some(id), _) => Err(EvalError::Upstream(id)),
+                (None, Some(inner)) => {
+                    let (result, changed) = self.evaluate_subflow(node, inner, &inputs);
+                    if changed {
+                        report.evaluated.push(node.id.clone());
+                    }
+                    result
+                }
+                (None, None) => {
+                    let cached = self
+                        .cache
+                        .get(&node.id)
+                        .filter(|entry| entry.matches(node, &inputs));
+                    match cached {
+                        Some(entry) => entry.result.clone(),
+                        None => {
+                            report.evaluated.push(node.id.clone());
+                            let result = compute(&self.registry, node, &inputs);
+                            self.cache.insert(
+                                node.id.clone(),
+                                CacheEntry {
+                                    kind: node.kind.clone(),
+                                    values: node.values.clone(),
+                                    inputs,
+                                    result: result.clone(),
+                                },
+                            );
+                            result
+                        }
+                    }
+                }
+            };
+            match result {
+                Ok(output_values) => {
+                    for (output, value) in node.outputs.iter().zip(output_values) {
+                        self.outputs.insert(output.id.clone(), value);
+                    }
+                }
+                Err(err) => {
+                    report.errors.insert(node.id.clone(), err);
+                }
+            }
+        }
+
+        // forget removed nodes
+        let node_ids = values
+            .nodes
+            .iter()
+            .map(|node| &node.id)
+            .collect::<HashSet<_>>();
+        self.cache.retain(|id, _| node_ids.contains(id));
+        self.subflows.retain(|id, _| node_ids.contains(id));
+        report
+    }
+
+    /// Evaluate the inner graph of a subflow node. The subflow node's inputs
+    /// and outputs forward to the inner connectors they expose.
+    /// Returns the result and whether any inner node was computed.
+    pub(crate) fn evaluate_subflow(
+        &mut self,
+        node: &Node,
+        inner: &YewFlowValues,
+        inputs: &[Option<FieldValue>],
+    ) -> (Result<Vec<FieldValue>, EvalError>, bool) {
+        let overrides = node
+            .inputs
+            .iter()
+            .zip(inputs)
+            .filter_map(|(input, value)| Some((input.exposed_id().clone(), value.clone()?)))
+            .collect::<HashMap<_, _>>();
+        let registry = self.registry.clone();
+        let evaluator = self
+            .subflows
+            .entry(node.id.clone())
+            .or_insert_with(|| Evaluator::new(registry));
+        let report = evaluator.evaluate_with(inner, &overrides);
+        let changed = !report.evaluated.is_empty();
+        if !report.errors.is_empty() {
+            return (Err(EvalError::Subflow(report.errors)), changed);
+        }
+        let output_values = node
+            .outputs
+            .iter()
+            .filter_map(|output| evaluator.output(output.exposed_id()).cloned())
+            .collect::<Vec<_>>();
+        (check_output_count(node, output_values), changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{edge::models::Edge, node::models::NodeTemplate};
+
+    /// Node of `kind` with `inputs` inputs and one output.
+    fn node(kind: &str, inputs: usize) -> Node {
+        NodeTemplate {
+            kind: Some(kind.to_string()),
+            title: kind.to_string(),
+            color: "#000000".to_string(),
+            inputs,
+            outputs: 1,
+            fields: Vec::new(),
+        }
+        .instantiate(0., 0.)
+    }
+
+    /// Constant node outputting `value`.
+    fn constant(value: f64) -> Node {
+        let mut node = node("const", 0);
+        node.values
+            .insert("value".to_string(), FieldValue::Number(value));
+        node
+    }
+
+    fn edge(from: &Node, to: &Node, input: usize) -> Edge {
+        Edge {
+            from_output: Some(from.outputs[0].id.clone()),
+            to_input: Some(to.inputs[input].id.clone()),
+            ..Default::default()
+        }
+    }
+
+    fn values(nodes: &[&Node], edges: Vec<Edge>) -> YewFlowValues {
+        YewFlowValues {
+            nodes: nodes.iter().map(|node| (*node).clone()).collect(),
+            edges,
+            groups: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    fn registry() -> ComputeRegistry {
+        let mut registry = ComputeRegistry::new();
+        registry
+            .register("const", |node, _| Ok(vec![node.values["value"].clone()]))
+            .register("add", |_, inputs| {
+                let sum = inputs
+                    .iter()
+                    .map(|input| match input {
+                        Some(FieldValue::Number(value)) => *value,
+                        _ => 0.,
+                    })
+                    .sum();
+                Ok(vec![FieldValue::Number(sum)])
+            })
+            .register("fail", |_, _| Err("boom".to_string()))
+            .register("none", |_, _| Ok(Vec::new()));
+        registry
+    }
+
+    #[test]
+    fn propagates_values_in_topological_order() {
+        let (a, b, sum) = (constant(1.), constant(2.), node("add", 2));
+        // listed downstream first
+        let values = values(&[&sum, &b, &a], vec![edge(&a, &sum, 0), edge(&b, &sum, 1)]);
+        let mut evaluator = Evaluator::new(registry());
+
+        let report = evaluator.evaluate(&values);
+        assert!(report.errors.is_empty());
+        assert_eq!(report.evaluated.len(), 3);
+        assert_eq!(report.evaluated.last(), Some(&sum.id));
+        assert_eq!(
+            evaluator.output(&sum.outputs[0].id),
+            Some(&FieldValue::Number(3.))
+        );
+    }
+
+    #[test]
+    fn reuses_cache_after_unrelated_edit() {
+        let (a, sum) = (constant(1.), node("add", 1));
+        let mut values = values(&[&a, &sum], vec![edge(&a, &sum, 0)]);
+        let mut evaluator = Evaluator::new(registry());
+        evaluator.evaluate(&values);
+
+        values.nodes[0].x = 100.;
+        values.nodes[1].title = "renamed".to_string();
+        let report = evaluator.evaluate(&values);
+        assert!(report.evaluated.is_empty());
+        assert_eq!(
+            evaluator.output(&sum.outputs[0].id),
+            Some(&FieldValue::Number(1.))
+        );
+
+        let other = constant(5.);
+        values.nodes.push(other.clone());
+        let report = evaluator.evaluate(&values);
+        assert_eq!(report.evaluated, vec![other.id]);
+    }
+
+    #[test]
+    fn recomputes_only_dirty_downstream_nodes() {
+        let (a, inc_a) = (constant(1.), node("add", 1));
+        let (b, inc_b) = (constant(2.), node("add", 1));
+        let mut values = values(
+            &[&a, &inc_a, &b, &inc_b],
+            vec![edge(&a, &inc_a, 0), edge(&b, &inc_b, 0)],
+        );
+        let mut evaluator = Evaluator::new(registry());
+        evaluator.evaluate(&values);
+
+        values.nodes[0]
+            .values
+            .insert("value".to_string(), FieldValue::Number(10.));
+        let report = evaluator.evaluate(&values);
+        assert_eq!(report.evaluated, vec![a.id.clone(), inc_a.id.clone()]);
+        assert_eq!(
+            evaluator.output(&inc_a.outputs[0].id),
+            Some(&FieldValue::Number(10.))
+        );
+        assert_eq!(
+            evaluator.output(&inc_b.outputs[0].id),
+            Some(&FieldValue::Number(2.))
+        );
+
+        // a node computing the same output again doesn't dirty its downstream nodes
+        values.nodes[2]
+            .values
+            .insert("unused".to_string(), FieldValue::Bool(true));
+        let report = evaluator.evaluate(&values);
+        assert_eq!(report.evaluated, vec![b.id]);
+    }
+
+    #[test]
+    fn reports_errors_per_node() {
+        let fail = node("fail", 0);
+        let unknown = node("unknown", 0);
+        let none = node("none", 0);
+        let mut missing = node("const", 0);
+        missing.kind = None;
+        let ok = constant(1.);
+        let values = values(&[&fail, &unknown, &none, &missing, &ok], Vec::new());
+        let mut evaluator = Evaluator::new(registry());
+
+        let report = evaluator.evaluate(&values);
+        assert_eq!(report.errors.len(), 4);
+        assert_eq!(
+            report.errors[&fail.id],
+            EvalError::Compute("boom".to_string())
+        );
+        assert_eq!(
+            report.errors[&unknown.id],
+            EvalError::UnknownKind("unknown".to_string())
+        );
+        assert_eq!(
+            report.errors[&none.id],
+            EvalError::OutputCount {
+                expected: 1,
+                actual: 0
+            }
+        );
+        assert_eq!(report.errors[&missing.id], EvalError::MissingKind);
+        assert_eq!(
+            evaluator.output(&ok.outputs[0].id),
+            Some(&FieldValue::Number(1.))
+        );
+    }
+
+    #[test]
+    fn reports_upstream_and_cycle_errors() {
+        let (fail, after_fail) = (node("fail", 0), node("add", 1));
+        let (first, second, after_cycle) = (node("add", 1), node("add", 1), node("add", 1));
+        let values = values(
+            &[&fail, &after_fail, &first, &second, &after_cycle],
+            vec![
+                edge(&fail, &after_fail, 0),
+                edge(&first, &second, 0),
+                edge(&second, &first, 0),
+                edge(&second, &after_cycle, 0),
+            ],
+        );
+        let mut evaluator = Evaluator::new(registry());
+
+        let report = evaluator.evaluate(&values);
+        assert_eq!(
+            report.errors[&after_fail.id],
+            EvalError::Upstream(fail.id.clone())
+        );
+        for node in [&first, &second, &after_cycle] {
+            assert_eq!(report.errors[&node.id], EvalError::Cycle);
+        }
+        assert_eq!(report.evaluated, vec![fail.id]);
+    }
+}
@@ -0,0 +1,850 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    components::node::models::{FieldValue, Node, NodeStatus},
+    eval::{check_output_count, compute, CacheEntry, ComputeRegistry, EvalError, Evaluator},
+    store::NodeStatusCmd,
+    types::standard_id::StandardId,
+    workspace::YewFlowValues,
+};
+
+/// Boxed future that does not need to be `Send`, as used in the browser.
+pub type LocalFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+/// Async compute function of a node kind. Like [`ComputeFn`](crate::eval::ComputeFn)
+/// but returns a future and gets a [`TaskContext`] to report progress.
+pub type AsyncComputeFn = Rc<
+    dyn Fn(
+        &Node,
+        &[Option<FieldValue>],
+        TaskContext,
+    ) -> LocalFuture<Result<Vec<FieldValue>, String>>,
+>;
+
+/// Runs a future to completion in the background, e.g.
+/// `wasm_bindgen_futures::spawn_local` in the browser.
+pub type Spawner = Rc<dyn Fn(LocalFuture<()>)>;
+
+/// Default number of async nodes running at the same time.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: bool,
+    /// Waker of the task, woken up to drop the task once cancelled.
+    waker: Option<Waker>,
+}
+
+/// # Task Context
+///
+/// Handed to async compute functions to report progress
+/// and to check whether the task was cancelled.
+#[derive(Clone)]
+pub struct TaskContext {
+    cancel: Rc<RefCell<CancelState>>,
+    progress: Rc<dyn Fn(f64)>,
+}
+
+impl TaskContext {
+    /// Whether the task was cancelled because the inputs of its node changed.
+    /// A cancelled task is dropped the next time it is polled, so checking
+    /// is only needed to stop blocking work early.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.borrow().cancelled
+    }
+
+    /// Report the progress of the task in percent.
+    pub fn set_progress(&self, percent: f64) {
+        if !self.is_cancelled() {
+            (self.progress)(percent.clamp(0., 100.))
+        }
+    }
+}
+
+/// Resolves to `None` as soon as the task is cancelled, dropping the inner future.
+struct Cancellable {
+    future: LocalFuture<Result<Vec<FieldValue>, String>>,
+    cancel: Rc<RefCell<CancelState>>,
+}
+
+impl Future for Cancellable {
+    type Output = Option<Result<Vec<FieldValue>, String>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        {
+            let mut cancel = self.cancel.borrow_mut();
+            if cancel.cancelled {
+                return Poll::Ready(None);
+            }
+            cancel.waker = Some(cx.waker().clone());
+        }
+        self.future.as_mut().poll(cx).map(Some)
+    }
+}
+
+/// Cancel a task and wake it up so that it gets dropped.
+fn cancel_task(cancel: &Rc<RefCell<CancelState>>) {
+    let waker = {
+        let mut cancel = cancel.borrow_mut();
+        cancel.cancelled = true;
+        cancel.waker.take()
+    };
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+struct RunningTask {
+    /// Tells results of replaced tasks apart.
+    generation: u64,
+    kind: Option<String>,
+    values: BTreeMap<String, FieldValue>,
+    inputs: Vec<Option<FieldValue>>,
+    cancel: Rc<RefCell<CancelState>>,
+}
+
+impl RunningTask {
+    fn matches(&self, node: &Node, inputs: &[Option<FieldValue>]) -> bool {
+        self.kind == node.kind && self.values == node.values && self.inputs == inputs
+    }
+}
+
+/// Async task of a node that is ready to be started.
+struct TaskStart {
+    node: Node,
+    inputs: Vec<Option<FieldValue>>,
+    compute: AsyncComputeFn,
+    generation: u64,
+    cancel: Rc<RefCell<CancelState>>,
+}
+
+struct ExecState {
+    registry: ComputeRegistry,
+    spawner: Spawner,
+    on_status: Rc<dyn Fn(NodeStatusCmd)>,
+    concurrency: usize,
+    values: YewFlowValues,
+    cache: HashMap<StandardId, CacheEntry>,
+    running: HashMap<StandardId, RunningTask>,
+    statuses: HashMap<StandardId, (NodeStatus, Option<f64>)>,
+    outputs: HashMap<StandardId, FieldValue>,
+    next_generation: u64,
+}
+
+impl ExecState {
+    /// Remember the status of a node, returning a command if it changed.
+    fn set_status(
+        &mut self,
+        id: &StandardId,
+        status: NodeStatus,
+        progress: Option<f64>,
+    ) -> Option<NodeStatusCmd> {
+        let state = (status, progress);
+        if self.statuses.get(id) == Some(&state) {
+            return None;
+        }
+        self.statuses.insert(id.clone(), state.clone());
+        Some(NodeStatusCmd {
+            id: id.clone(),
+            status: state.0,
+            progress: state.1,
+        })
+    }
+}
+
+/// Status of a node with a finished computation.
+fn result_status(result: &Result<Vec<FieldValue>, EvalError>) -> NodeStatus {
+    match result {
+        Ok(_) => NodeStatus::Done,
+        Err(err) => NodeStatus::Error(err.to_string()),
+    }
+}
+
+/// # Executor
+///
+/// Runs a flow with async nodes. Like the [`Evaluator`] it computes nodes in
+/// topological order and caches their results, but async compute functions
+/// run in the background, at most `concurrency` at a time, so independent
+/// branches make progress in parallel.
+///
+/// - A running task is cancelled when the inputs or fields of its node change.
+/// - Status and progress changes of nodes are reported through `on_status`,
+///   e.g. to update `Node::status`/`Node::progress` of the host's values.
+/// - Subflow nodes are evaluated synchronously.
+#[derive(Clone)]
+pub struct Executor {
+    state: Rc<RefCell<ExecState>>,
+}
+
+impl Executor {
+    pub fn new(
+        registry: ComputeRegistry,
+        spawner: impl Fn(LocalFuture<()>) + 'static,
+        on_status: impl Fn(NodeStatusCmd) + 'static,
+    ) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(ExecState {
+                registry,
+                spawner: Rc::new(spawner),
+                on_status: Rc::new(on_status),
+                concurrency: DEFAULT_CONCURRENCY,
+                values: YewFlowValues {
+                    nodes: Vec::new(),
+                    edges: Vec::new(),
+                    groups: Vec::new(),
+                    comments: Vec::new(),
+                },
+                cache: HashMap::new(),
+                running: HashMap::new(),
+                statuses: HashMap::new(),
+                outputs: HashMap::new(),
+                next_generation: 0,
+            })),
+        }
+    }
+
+    /// Limit the number of async nodes running at the same time.
+    pub fn with_concurrency(self, concurrency: usize) -> Self {
+        self.state.borrow_mut().concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Run `values`. Nodes whose inputs did not change keep their results,
+    /// tasks of changed nodes are cancelled and started again.
+    pub fn run(&self, values: &YewFlowValues) {
+        self.state.borrow_mut().values = values.clone();
+        schedule(&self.state);
+    }
+
+    /// Cancel all running tasks.
+    pub fn cancel_all(&self) {
+        let (tasks, on_status, cmds) = {
+            let mut state = self.state.borrow_mut();
+            let tasks = state.running.drain().collect::<Vec<_>>();
+            let cmds = tasks
+                .iter()
+                .filter_map(|(id, _)| state.set_status(id, NodeStatus::Idle, None))
+                .collect::<Vec<_>>();
+            (tasks, state.on_status.clone(), cmds)
+        };
+        for (_, task) in tasks {
+            cancel_task(&task.cancel);
+        }
+        for cmd in cmds {
+            on_status(cmd);
+        }
+    }
+
+    /// Value of the output with `id`, if its node is done.
+    pub fn output(&self, id: &StandardId) -> Option<FieldValue> {
+        self.state.borrow().outputs.get(id).cloned()
+    }
+
+    /// Status and progress of the node with `id`.
+    pub fn status(&self, id: &StandardId) -> (NodeStatus, Option<f64>) {
+        self.state
+            .borrow()
+            .statuses
+            .get(id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Whether no task is running.
+    pub fn is_idle(&self) -> bool {
+        self.state.borrow().running.is_empty()
+    }
+}
+
+/// Compute everything that can be computed with the current values
+/// and start the async tasks of nodes with available inputs.
+fn schedule(state_rc: &Rc<RefCell<ExecState>>) {
+    let mut cmds = Vec::new();
+    let mut starts = Vec::new();
+    let mut cancelled = Vec::new();
+    let (spawner, on_status) = {
+        let mut state = state_rc.borrow_mut();
+        let state = &mut *state;
+        let values = state.values.clone();
+        let node_of_output = values
+            .nodes
+            .iter()
+            .flat_map(|node| node.outputs.iter().map(move |output| (&output.id, node)))
+            .collect::<HashMap<_, _>>();
+        let source_of_input = values
+            .edges
+            .iter()
+            .filter_map(|edge| edge.to_input.as_ref().zip(edge.from_output.as_ref()))
+            .collect::<HashMap<_, _>>();
+        let (order, cyclic) = values.graph().topological_order();
+
+        // forget removed nodes
+        let node_ids = values
+            .nodes
+            .iter()
+            .map(|node| &node.id)
+            .collect::<HashSet<_>>();
+        state.cache.retain(|id, _| node_ids.contains(id));
+        state.statuses.retain(|id, _| node_ids.contains(id));
+        let removed = state
+            .running
+            .keys()
+            .filter(|id| !node_ids.contains(id))
+            .cloned()
+            .collect::<Vec<_>>();
+        for id in removed {
+            cancelled.extend(state.running.remove(&id).map(|task| task.cancel));
+        }
+
+        state.outputs.clear();
+        let mut failed = HashSet::new();
+        for node in cyclic {
+            failed.insert(node.id.clone());
+            if let Some(task) = state.running.remove(&node.id) {
+                cancelled.push(task.cancel);
+            }
+            let status = NodeStatus::Error(EvalError::Cycle.to_string());
+            cmds.extend(state.set_status(&node.id, status, None));
+        }
+        for node in order {
+            let mut waiting = false;
+            let mut failed_source = None;
+            let inputs = node
+                .inputs
+                .iter()
+                .map(|input| match source_of_input.get(&input.id) {
+                    Some(output_id) => {
+                        let value = state.outputs.get(*output_id).cloned();
+                        match node_of_output.get(output_id) {
+                            Some(source) if failed.contains(&source.id) => {
+                                failed_source = Some(source.id.clone())
+                            }
+                            Some(_) if value.is_none() => waiting = true,
+                            _ => {}
+                        }
+                        value
+                    }
+                    None => input.inline_value(),
+                })
+                .collect::<Vec<_>>();
+
+            if failed_source.is_some() || waiting {
+                // running tasks were started with outdated inputs
+                if let Some(task) = state.running.remove(&node.id) {
+                    cancelled.push(task.cancel);
+                }
+                let status = if let Some(source_id) = failed_source {
+                    failed.insert(node.id.clone());
+                    NodeStatus::Error(EvalError::Upstream(source_id).to_string())
+                } else {
+                    NodeStatus::Idle
+                };
+                cmds.extend(state.set_status(&node.id, status, None));
+                continue;
+            }
+
+            let cached = state
+                .cache
+                .get(&node.id)
+                .filter(|entry| entry.matches(node, &inputs))
+                .map(|entry| entry.result.clone());
+            let async_compute = node
+                .kind
+                .as_ref()
+                .and_then(|kind| state.registry.get_async(kind))
+                .filter(|_| node.subflow.is_none())
+                .cloned();
+            let result = match (cached, async_compute) {
+                (Some(result), _) => Some(result),
+                (None, Some(async_compute)) => {
+                    let is_running = match state.running.get(&node.id) {
+                        Some(task) if task.matches(node, &inputs) => true,
+                        Some(_) => {
+                            // inputs changed, restart the task
+                            let task = state.running.remove(&node.id);
+                            cancelled.extend(task.map(|task| task.cancel));
+                            false
+                        }
+                        None => false,
+                    };
+                    if !is_running && state.running.len() < state.concurrency {
+                        let generation = state.next_generation;
+                        state.next_generation += 1;
+                        let cancel = Rc::new(RefCell::new(CancelState::default()));
+                        state.running.insert(
+                            node.id.clone(),
+                            RunningTask {
+                                generation,
+                                kind: node.kind.clone(),
+                                values: node.values.clone(),
+                                inputs: inputs.clone(),
+                                cancel: cancel.clone(),
+                            },
+                        );
+                        starts.push(TaskStart {
+                            node: node.clone(),
+                            inputs,
+                            compute: async_compute,
+                            generation,
+                            cancel,
+                        });
+                        cmds.extend(state.set_status(&node.id, NodeStatus::Running, Some(0.)));
+                    } else if !is_running {
+                        // queued until a running task finishes
+                        cmds.extend(state.set_status(&node.id, NodeStatus::Idle, None));
+                    }
+                    None
+                }
+                (None, None) => {
+                    let result = match node.subflow {
+                        Some(ref inner) => {
+                            Evaluator::new(state.registry.clone())
+                                .evaluate_subflow(node, inner, &inputs)
+                                .0
+                        }
+                        None => compute(&state.registry, node, &inputs),
+                    };
+                    state.cache.insert(
+                        node.id.clone(),
+                        CacheEntry {
+                            kind: node.kind.clone(),
+                            values: node.values.clone(),
+                            inputs,
+                            result: result.clone(),
+                        },
+                    );
+                    Some(result)
+                }
+            };
+            // without a result, downstream nodes wait for the task
+            if let Some(result) = result {
+                cmds.extend(state.set_status(&node.id, result_status(&result), None));
+                match result {
+                    Ok(output_values) => {
+                        for (output, value) in node.outputs.iter().zip(output_values) {
+                            state.outputs.insert(output.id.clone(), value);
+                        }
+                    }
+                    Err(_) => {
+                        failed.insert(node.id.clone());
+                    }
+                }
+            }
+        }
+        (state.spawner.clone(), state.on_status.clone())
+    };
+
+    for cancel in cancelled {
+        cancel_task(&cancel);
+    }
+    for cmd in cmds {
+        on_status(cmd);
+    }
+    for start in starts {
+        spawner(start_task(state_rc, start));
+    }
+}
+
+/// Create the future of an async task. Once it finishes, its result is
+/// cached and the downstream nodes are scheduled.
+fn start_task(state_rc: &Rc<RefCell<ExecState>>, start: TaskStart) -> LocalFuture<()> {
+    let TaskStart {
+        node,
+        inputs,
+        compute,
+        generation,
+        cancel,
+    } = start;
+    let weak = Rc::downgrade(state_rc);
+    let progress = {
+        let weak = weak.clone();
+        let id = node.id.clone();
+        Rc::new(move |percent: f64| {
+            if let Some(state_rc) = weak.upgrade() {
+                let (cmd, on_status) = {
+                    let mut state = state_rc.borrow_mut();
+                    let is_current = state
+                        .running
+                        .get(&id)
+                        .map(|task| task.generation == generation)
+                        .unwrap_or_default();
+                    let cmd = is_current
+                        .then(|| state.set_status(&id, NodeStatus::Running, Some(percent)))
+                        .flatten();
+                    (cmd, state.on_status.clone())
+                };
+                if let Some(cmd) = cmd {
+                    on_status(cmd);
+                }
+            }
+        })
+    };
+    let context = TaskContext {
+        cancel: cancel.clone(),
+        progress,
+    };
+    let future = Cancellable {
+        future: compute(&node, &inputs, context),
+        cancel,
+    };
+    Box::pin(async move {
+        if let Some(result) = future.await {
+            finish_task(&weak, node, inputs, generation, result);
+        }
+    })
+}
+
+/// Store the result of a finished task and continue with the downstream nodes.
+fn finish_task(
+    weak: &Weak<RefCell<ExecState>>,
+    node: Node,
+    inputs: Vec<Option<FieldValue>>,
+    generation: u64,
+    result: Result<Vec<FieldValue>, String>,
+) {
+    let state_rc = match weak.upgrade() {
+        Some(state_rc) => state_rc,
+        None => return,
+    };
+    {
+        let mut state = state_rc.borrow_mut();
+        let is_current = state
+            .running
+            .get(&node.id)
+            .map(|task| task.generation == generation)
+            .unwrap_or_default();
+        if !is_current {
+            return;
+        }
+        state.running.remove(&node.id);
+        let result = result
+            .map_err(EvalError::Compute)
+            .and_then(|output_values| check_output_count(&node, output_values));
+        state.cache.insert(
+            node.id.clone(),
+            CacheEntry {
+                kind: node.kind.clone(),
+                values: node.values.clone(),
+                inputs,
+                result,
+            },
+        );
+    }
+    schedule(&state_rc);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::poll_fn, sync::Arc, task::Wake};
+
+    use super::*;
+    use crate::components::{edge::models::Edge, node::models::NodeTemplate};
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    type TaskResult = Result<Vec<FieldValue>, String>;
+
+    /// Call of the async compute function, finished by the test.
+    struct Call {
+        node_id: StandardId,
+        inputs: Vec<Option<FieldValue>>,
+        context: TaskContext,
+        result: Rc<RefCell<Option<TaskResult>>>,
+    }
+
+    impl Call {
+        fn finish(&self, value: f64) {
+            *self.result.borrow_mut() = Some(Ok(vec![FieldValue::Number(value)]));
+        }
+    }
+
+    /// Executor with a manual spawner, recording compute calls and statuses.
+    struct Harness {
+        executor: Executor,
+        tasks: Rc<RefCell<Vec<LocalFuture<()>>>>,
+        calls: Rc<RefCell<Vec<Call>>>,
+        statuses: Rc<RefCell<Vec<NodeStatusCmd>>>,
+    }
+
+    impl Harness {
+        fn new(concurrency: usize) -> Self {
+            let calls = Rc::new(RefCell::new(Vec::<Call>::new()));
+            let mut registry = ComputeRegistry::new();
+            registry.register("const", |node, _| Ok(vec![node.values["value"].clone()]));
+            registry.register("fail", |_, _| Err("boom".to_string()));
+            {
+                let calls = calls.clone();
+                registry.register_async("slow", move |node, inputs, context| {
+                    let result = Rc::new(RefCell::new(None));
+                    calls.borrow_mut().push(Call {
+                        node_id: node.id.clone(),
+                        inputs: inputs.to_vec(),
+                        context,
+                        result: result.clone(),
+                    });
+                    Box::pin(poll_fn(move |_| match result.borrow_mut().take() {
+                        Some(result) => Poll::Ready(result),
+                        None => Poll::Pending,
+                    }))
+                });
+            }
+            let tasks = Rc::new(RefCell::new(Vec::new()));
+            let statuses = Rc::new(RefCell::new(Vec::new()));
+            let executor = {
+                let tasks = tasks.clone();
+                let statuses = statuses.clone();
+                Executor::new(
+                    registry,
+                    move |task| tasks.borrow_mut().push(task),
+                    move |cmd| statuses.borrow_mut().push(cmd),
+                )
+                .with_concurrency(concurrency)
+            };
+            Self {
+                executor,
+                tasks,
+                calls,
+                statuses,
+            }
+        }
+
+        /// Poll the spawned tasks until none of them can make progress.
+        fn poll(&self) {
+            let waker = Arc::new(NoopWaker).into();
+            let mut cx = Context::from_waker(&waker);
+            loop {
+                let tasks = self.tasks.take();
+                if tasks.is_empty() {
+                    return;
+                }
+                let count = tasks.len();
+                let mut pending = Vec::new();
+                for mut task in tasks {
+                    if task.as_mut().poll(&mut cx).is_pending() {
+                        pending.push(task);
+                    }
+                }
+                let mut tasks = self.tasks.borrow_mut();
+                let stalled = pending.len() == count && tasks.is_empty();
+                tasks.splice(0..0, pending);
+                if stalled {
+                    return;
+                }
+            }
+        }
+
+        fn statuses_of(&self, id: &StandardId) -> Vec<(NodeStatus, Option<f64>)> {
+            self.statuses
+                .borrow()
+                .iter()
+                .filter(|cmd| cmd.id == *id)
+                .map(|cmd| (cmd.status.clone(), cmd.progress))
+                .collect()
+        }
+    }
+
+    fn node(kind: &str, inputs: usize) -> Node {
+        NodeTemplate {
+            kind: Some(kind.to_string()),
+            title: kind.to_string(),
+            color: "#000000".to_string(),
+            inputs,
+            outputs: 1,
+            fields: Vec::new(),
+        }
+        .instantiate(0., 0.)
+    }
+
+    fn constant(value: f64) -> Node {
+        let mut node = node("const", 0);
+        node.values
+            .insert("value".to_string(), FieldValue::Number(value));
+        node
+    }
+
+    fn edge(from: &Node, to: &Node) -> Edge {
+        Edge {
+            from_output: Some(from.outputs[0].id.clone()),
+            to_input: Some(to.inputs[0].id.clone()),
+            ..Default::default()
+        }
+    }
+
+    fn values(nodes: &[&Node], edges: Vec<Edge>) -> YewFlowValues {
+        YewFlowValues {
+            nodes: nodes.iter().map(|node| (*node).clone()).collect(),
+            edges,
+            groups: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn reports_statuses_and_progress() {
+        let harness = Harness::new(DEFAULT_CONCURRENCY);
+        let (source, slow) = (constant(1.), node("slow", 1));
+        let values = values(&[&source, &slow], vec![edge(&source, &slow)]);
+        harness.executor.run(&values);
+        harness.poll();
+        assert_eq!(
+            harness.statuses_of(&source.id),
+            vec![(NodeStatus::Done, None)]
+        );
+        assert_eq!(
+            harness.calls.borrow()[0].inputs,
+            vec![Some(FieldValue::Number(1.))]
+        );
+
+        harness.calls.borrow()[0].context.set_progress(50.);
+        harness.calls.borrow()[0].finish(2.);
+        harness.poll();
+        assert_eq!(
+            harness.statuses_of(&slow.id),
+            vec![
+                (NodeStatus::Running, Some(0.)),
+                (NodeStatus::Running, Some(50.)),
+                (NodeStatus::Done, None),
+            ]
+        );
+        assert_eq!(
+            harness.executor.output(&slow.outputs[0].id),
+            Some(FieldValue::Number(2.))
+        );
+        assert!(harness.executor.is_idle());
+
+        // unchanged values keep the cached result
+        harness.executor.run(&values);
+        harness.poll();
+        assert_eq!(harness.calls.borrow().len(), 1);
+    }
+
+    #[test]
+    fn cancels_tasks_when_inputs_change() {
+        let harness = Harness::new(DEFAULT_CONCURRENCY);
+        let (source, slow) = (constant(1.), node("slow", 1));
+        let mut values = values(&[&source, &slow], vec![edge(&source, &slow)]);
+        harness.executor.run(&values);
+        harness.poll();
+
+        values.nodes[0]
+            .values
+            .insert("value".to_string(), FieldValue::Number(5.));
+        harness.executor.run(&values);
+        harness.poll();
+        let calls = harness.calls.borrow();
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].context.is_cancelled());
+        assert!(!calls[1].context.is_cancelled());
+        assert_eq!(calls[1].inputs, vec![Some(FieldValue::Number(5.))]);
+    }
+
+    #[test]
+    fn drops_results_of_stale_tasks() {
+        let harness = Harness::new(DEFAULT_CONCURRENCY);
+        let (source, slow) = (constant(1.), node("slow", 1));
+        let mut values = values(&[&source, &slow], vec![edge(&source, &slow)]);
+        harness.executor.run(&values);
+        harness.poll();
+        values.nodes[0]
+            .values
+            .insert("value".to_string(), FieldValue::Number(5.));
+        harness.executor.run(&values);
+
+        // the replaced task finishes and reports progress late
+        harness.calls.borrow()[0].context.set_progress(90.);
+        harness.calls.borrow()[0].finish(100.);
+        harness.poll();
+        assert_eq!(harness.executor.output(&slow.outputs[0].id), None);
+        assert_eq!(
+            harness.executor.status(&slow.id),
+            (NodeStatus::Running, Some(0.))
+        );
+
+        harness.calls.borrow()[1].finish(6.);
+        harness.poll();
+        assert_eq!(
+            harness.executor.output(&slow.outputs[0].id),
+            Some(FieldValue::Number(6.))
+        );
+        assert!(!harness
+            .statuses_of(&slow.id)
+            .contains(&(NodeStatus::Running, Some(90.))));
+    }
+
+    #[test]
+    fn queues_tasks_beyond_the_concurrency_limit() {
+        let harness = Harness::new(1);
+        let (first, second) = (node("slow", 0), node("slow", 0));
+        harness
+            .executor
+            .run(&values(&[&first, &second], Vec::new()));
+        harness.poll();
+        assert_eq!(harness.calls.borrow().len(), 1);
+        let started = harness.calls.borrow()[0].node_id.clone();
+        let queued = if started == first.id {
+            &second.id
+        } else {
+            &first.id
+        };
+        assert_eq!(harness.executor.status(queued), (NodeStatus::Idle, None));
+
+        harness.calls.borrow()[0].finish(1.);
+        harness.poll();
+        assert_eq!(harness.calls.borrow().len(), 2);
+        assert_eq!(harness.calls.borrow()[1].node_id, *queued);
+        assert_eq!(
+            harness.executor.status(queued),
+            (NodeStatus::Running, Some(0.))
+        );
+
+        harness.calls.borrow()[1].finish(2.);
+        harness.poll();
+        assert!(harness.executor.is_idle());
+        for id in [&first.id, &second.id] {
+            assert_eq!(harness.executor.status(id), (NodeStatus::Done, None));
+        }
+    }
+
+    #[test]
+    fn reports_upstream_errors_and_cycles() {
+        let harness = Harness::new(DEFAULT_CONCURRENCY);
+        let (fail, after_fail) = (node("fail", 0), node("slow", 1));
+        let (first, second) = (node("slow", 1), node("slow", 1));
+        let values = values(
+            &[&fail, &after_fail, &first, &second],
+            vec![
+                edge(&fail, &after_fail),
+                edge(&first, &second),
+                edge(&second, &first),
+            ],
+        );
+        harness.executor.run(&values);
+        harness.poll();
+        assert!(harness.calls.borrow().is_empty());
+        assert_eq!(
+            harness.executor.status(&fail.id).0,
+            NodeStatus::Error("boom".to_string())
+        );
+        assert_eq!(
+            harness.executor.status(&after_fail.id).0,
+            NodeStatus::Error(EvalError::Upstream(fail.id.clone()).to_string())
+        );
+        for id in [&first.id, &second.id] {
+            assert_eq!(
+                harness.executor.status(id).0,
+                NodeStatus::Error(EvalError::Cycle.to_string())
+            );
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::workspace::YewFlowValues;
+
+/// Version of the file format written by this crate.
+///
+/// Bump it together with a new entry in `MIGRATIONS` and a new fixture
+/// in `fixtures/` (checked by `tests/fixtures.rs`) whenever saved flows
+/// would no longer load as they are.
+///
+/// - `0`: bare `YewFlowValues` without an envelope.
+/// - `1`: `FlowDocument` envelope with metadata.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Upgrade of a document from the version at its index to the next one.
+type Migration = fn(Value) -> Result<Value, FormatError>;
+
+const MIGRATIONS: [Migration; FORMAT_VERSION as usize] = [migrate_v0_to_v1];
+
+/// # Format Error
+///
+/// Reasons a saved flow could not be loaded.
+#[derive(Debug)]
+pub enum FormatError {
+    /// The text is not valid JSON or does not match the format.
+    Json(serde_json::Error),
+    /// The document was written by a newer version of the crate.
+    UnsupportedVersion(u32),
+    /// The document could not be upgraded from version `from`.
+    Migration { from: u32, message: String },
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::Json(err) => write!(f, "{}", err),
+            FormatError::UnsupportedVersion(version) => write!(
+                f,
+                "format version {} is newer than the supported version {}",
+                version, FORMAT_VERSION
+            ),
+            FormatError::Migration { from, message } => {
+                write!(
+                    f,
+                    "cannot upgrade from format version {}: {}",
+                    from, message
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<serde_json::Error> for FormatError {
+    fn from(err: serde_json::Error) -> Self {
+        FormatError::Json(err)
+    }
+}
+
+/// Descriptive data of a saved flow. Timestamps are milliseconds since the
+/// Unix epoch and are set by the host, e.g. from `Date.now()`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FlowMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Unknown for documents upgraded from version `0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<u64>,
+}
+
+/// # Flow Document
+///
+/// Versioned envelope of a saved flow. Use [`FlowDocument::from_json_str`]
+/// to load documents of any version; older ones are upgraded on the way.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FlowDocument {
+    /// Format version the document was written with.
+    pub version: u32,
+    #[serde(default)]
+    pub metadata: FlowMetadata,
+    pub flow: YewFlowValues,
+}
+
+impl FlowDocument {
+    /// New document of the current version created at `now`.
+    pub fn new(flow: YewFlowValues, now: Option<u64>) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            metadata: FlowMetadata {
+                created: now,
+                modified: now,
+                ..Default::default()
+            },
+            flow,
+        }
+    }
+
+    /// Replace the flow and update the modification time.
+    pub fn update(&mut self, flow: YewFlowValues, now: Option<u64>) {
+        self.flow = flow;
+        if now.is_some() {
+            self.metadata.modified = now;
+        }
+    }
+
+    /// Parse a document of any known version, upgrading it to the current one.
+    pub fn from_json_str(text: &str) -> Result<Self, FormatError> {
+        let value = serde_json::from_str::<Value>(text)?;
+        Ok(serde_json::from_value(migrate(value)?)?)
+    }
+
+    pub fn to_json_string(&self) -> Result<String, FormatError> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// JSON Schema of documents of the current version, for validating flows
+/// outside of Rust and for editor autocompletion.
+#[cfg(feature = "schema")]
+pub fn document_schema() -> schemars::schema::RootSchema {
+    schemars::schema_for!(FlowDocument)
+}
+
+/// JSON Schema of the bare values inside a document.
+#[cfg(feature = "schema")]
+pub fn values_schema() -> schemars::schema::RootSchema {
+    schemars::schema_for!(YewFlowValues)
+}
+
+/// Version of a parsed document. Documents without a version are bare
+/// `YewFlowValues` as written before the envelope existed.
+pub fn document_version(value: &Value) -> Result<u32, FormatError> {
+    match value.get("version") {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| FormatError::Migration {
+                from: 0,
+                message: format!("invalid version {}", version),
+            }),
+    }
+}
+
+/// Upgrade a parsed document to [`FORMAT_VERSION`] by running the
+/// migrations of all versions in between.
+pub fn migrate(mut value: Value) -> Result<Value, FormatError> {
+    let version = document_version(&value)?;
+    if version > FORMAT_VERSION {
+        return Err(FormatError::UnsupportedVersion(version));
+    }
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        value = migration(value)?;
+    }
+    Ok(value)
+}
+
+/// Wrap bare values into the envelope.
+fn migrate_v0_to_v1(value: Value) -> Result<Value, FormatError> {
+    if !value.is_object() {
+        return Err(FormatError::Migration {
+            from: 0,
+            message: "expected an object with nodes and edges".to_string(),
+        });
+    }
+    Ok(json!({
+        "version": 1,
+        "metadata": {},
+        "flow": value,
+    }))
+}
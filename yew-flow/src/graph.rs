@@ -0,0 +1,689 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{edge::models::Edge, node::models::Node},
+    types::standard_id::StandardId,
+    workspace::YewFlowValues,
+};
+
+/// Edge connecting an output of node `from` to an input of node `to`
+/// (indices into `FlowGraph::nodes`).
+struct Link<'a> {
+    edge: &'a Edge,
+    from: usize,
+    to: usize,
+}
+
+/// # Flow Graph
+///
+/// Node level view of a flow for graph algorithms. Edges with a loose
+/// end or with connectors of unknown nodes are left out.
+pub struct FlowGraph<'a> {
+    nodes: Vec<&'a Node>,
+    links: Vec<Link<'a>>,
+    /// Indices of the links leaving each node.
+    downstream: Vec<Vec<usize>>,
+}
+
+impl<'a> FlowGraph<'a> {
+    pub fn new(nodes: &'a [Node], edges: &'a [Edge]) -> Self {
+        let node_of_output = nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, node)| node.outputs.iter().map(move |output| (&output.id, i)))
+            .collect::<HashMap<_, _>>();
+        let node_of_input = nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, node)| node.inputs.iter().map(move |input| (&input.id, i)))
+            .collect::<HashMap<_, _>>();
+        let links = edges
+            .iter()
+            .filter_map(|edge| {
+                let from = node_of_output.get(edge.from_output.as_ref()?)?;
+                let to = node_of_input.get(edge.to_input.as_ref()?)?;
+                Some(Link {
+                    edge,
+                    from: *from,
+                    to: *to,
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut downstream = vec![Vec::new(); nodes.len()];
+        for (i, link) in links.iter().enumerate() {
+            downstream[link.from].push(i);
+        }
+        Self {
+            nodes: nodes.iter().collect(),
+            links,
+            downstream,
+        }
+    }
+
+    /// Order the nodes so that every node comes after the nodes feeding it.
+    /// Returns the ordered nodes and the nodes that are part of or fed by a cycle.
+    pub fn topological_order(&self) -> (Vec<&'a Node>, Vec<&'a Node>) {
+        let mut in_degree = vec![0; self.nodes.len()];
+        for link in self.links.iter() {
+            in_degree[link.to] += 1;
+        }
+        let mut queue = (0..self.nodes.len())
+            .filter(|i| in_degree[*i] == 0)
+            .collect::<VecDeque<_>>();
+        let mut ordered = vec![false; self.nodes.len()];
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(i) = queue.pop_front() {
+            ordered[i] = true;
+            order.push(self.nodes[i]);
+            for link in self.downstream[i].iter() {
+                let next = self.links[*link].to;
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+        let cyclic = (0..self.nodes.len())
+            .filter(|i| !ordered[*i])
+            .map(|i| self.nodes[i])
+            .collect();
+        (order, cyclic)
+    }
+
+    /// Strongly connected components, i.e. groups of nodes that can all reach
+    /// each other. Nodes that are not part of a cycle form a component of their
+    /// own. Components are listed downstream first.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&'a Node>> {
+        self.component_indices()
+            .into_iter()
+            .map(|component| component.into_iter().map(|i| self.nodes[i]).collect())
+            .collect()
+    }
+
+    /// Tarjan's algorithm, iterative so that long chains don't overflow the stack.
+    fn component_indices(&self) -> Vec<Vec<usize>> {
+        let count = self.nodes.len();
+        let mut index = vec![usize::MAX; count];
+        let mut low_link = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+        for root in 0..count {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            // (node, position in its list of downstream links)
+            let mut calls = vec![(root, 0)];
+            index[root] = next_index;
+            low_link[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            while let Some((node, position)) = calls.pop() {
+                if let Some(link) = self.downstream[node].get(position) {
+                    calls.push((node, position + 1));
+                    let next = self.links[*link].to;
+                    if index[next] == usize::MAX {
+                        index[next] = next_index;
+                        low_link[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        calls.push((next, 0));
+                    } else if on_stack[next] {
+                        low_link[node] = low_link[node].min(index[next]);
+                    }
+                    continue;
+                }
+                // all links of `node` are visited
+                if let Some((parent, _)) = calls.last() {
+                    low_link[*parent] = low_link[*parent].min(low_link[node]);
+                }
+                if low_link[node] == index[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// Edges that are part of a cycle. Removing all of them makes the flow acyclic.
+    pub fn cycle_edges(&self) -> Vec<&'a Edge> {
+        let mut component_of = vec![0; self.nodes.len()];
+        let mut cyclic = Vec::new();
+        for (c, component) in self.component_indices().into_iter().enumerate() {
+            cyclic.push(component.len() > 1);
+            for i in component {
+                component_of[i] = c;
+            }
+        }
+        self.links
+            .iter()
+            .filter(|link| {
+                let component = component_of[link.from];
+                link.from == link.to || (component == component_of[link.to] && cyclic[component])
+            })
+            .map(|link| link.edge)
+            .collect()
+    }
+}
+
+impl YewFlowValues {
+    /// Graph of the nodes and edges, see [`FlowGraph`].
+    pub fn graph(&self) -> FlowGraph<'_> {
+        FlowGraph::new(&self.nodes, &self.edges)
+    }
+}
+
+/// Chain of nodes and the edges between them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphPath {
+    pub nodes: Vec<StandardId>,
+    pub edges: Vec<StandardId>,
+}
+
+/// # Highlight Mode
+///
+/// Which connections of the hovered or selected node are emphasized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightMode {
+    Off,
+    /// Everything feeding the node.
+    Upstream,
+    /// Everything fed by the node.
+    Downstream,
+    Both,
+}
+
+impl Default for HighlightMode {
+    fn default() -> Self {
+        HighlightMode::Off
+    }
+}
+
+impl HighlightMode {
+    fn upstream(&self) -> bool {
+        matches!(self, HighlightMode::Upstream | HighlightMode::Both)
+    }
+
+    fn downstream(&self) -> bool {
+        matches!(self, HighlightMode::Downstream | HighlightMode::Both)
+    }
+}
+
+/// Ids of the emphasized nodes and edges. Everything else is dimmed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Highlight {
+    pub nodes: HashSet<StandardId>,
+    pub edges: HashSet<StandardId>,
+}
+
+/// # Graph Index
+///
+/// Lookups of the connections between nodes, kept by `WorkspaceStore`.
+/// Unlike [`FlowGraph`] it owns its data and is updated in place by the
+/// store as nodes and edges are added, reconnected or removed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphIndex {
+    /// Node id by connector id.
+    port_nodes: HashMap<StandardId, StandardId>,
+    /// Connector ids (inputs, then outputs) by node id.
+    node_ports: HashMap<StandardId, Vec<StandardId>>,
+    /// Connected output and input by edge id.
+    edge_ports: HashMap<StandardId, (Option<StandardId>, Option<StandardId>)>,
+    /// Ids of the edges attached to a connector by connector id, sorted.
+    port_edges: HashMap<StandardId, Vec<StandardId>>,
+}
+
+impl GraphIndex {
+    pub fn new(nodes: &[Node], edges: &[Edge]) -> Self {
+        let mut index = Self::default();
+        for node in nodes.iter() {
+            index.insert_node(node);
+        }
+        for edge in edges.iter() {
+            index.insert_edge(edge);
+        }
+        index
+    }
+
+    /// Add `node` or update the connectors of an indexed node.
+    pub fn insert_node(&mut self, node: &Node) {
+        let ports = node.port_ids();
+        if self.node_ports.get(&node.id) != Some(&ports) {
+            self.remove_node(&node.id);
+            for port in ports.iter() {
+                self.port_nodes.insert(port.clone(), node.id.clone());
+            }
+            self.node_ports.insert(node.id.clone(), ports);
+        }
+    }
+
+    /// Remove the node with `id`. Its edges stay indexed until removed.
+    pub fn remove_node(&mut self, id: &StandardId) {
+        for port in self.node_ports.remove(id).into_iter().flatten() {
+            // the connector may have been moved to another node already
+            if self.port_nodes.get(&port) == Some(id) {
+                self.port_nodes.remove(&port);
+            }
+        }
+    }
+
+    /// Add `edge` or update the connectors of an indexed edge.
+    pub fn insert_edge(&mut self, edge: &Edge) {
+        let ports = (edge.from_output.clone(), edge.to_input.clone());
+        if self.edge_ports.get(&edge.id) != Some(&ports) {
+            self.remove_edge(&edge.id);
+            for port in [&ports.0, &ports.1].into_iter().flatten() {
+                let edge_ids = self.port_edges.entry(port.clone()).or_default();
+                if let Err(i) = edge_ids.binary_search(&edge.id) {
+                    edge_ids.insert(i, edge.id.clone());
+                }
+            }
+            self.edge_ports.insert(edge.id.clone(), ports);
+        }
+    }
+
+    /// Remove the edge with `id`.
+    pub fn remove_edge(&mut self, id: &StandardId) {
+        if let Some((from, to)) = self.edge_ports.remove(id) {
+            for port in [from, to].into_iter().flatten() {
+                if let Some(edge_ids) = self.port_edges.get_mut(&port) {
+                    edge_ids.retain(|edge_id| edge_id != id);
+                    if edge_ids.is_empty() {
+                        self.port_edges.remove(&port);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Id of the node owning the connector with `port_id`.
+    pub fn node_of_port(&self, port_id: &StandardId) -> Option<&StandardId> {
+        self.port_nodes.get(port_id)
+    }
+
+    /// Ids of the nodes connected by the edge with `edge_id`, if connected.
+    pub fn edge_nodes(&self, edge_id: &StandardId) -> Option<(&StandardId, &StandardId)> {
+        let (from, to) = self.edge_ports.get(edge_id)?;
+        Some((
+            self.node_of_port(from.as_ref()?)?,
+            self.node_of_port(to.as_ref()?)?,
+        ))
+    }
+
+    /// Ids of the edges attached to the connector with `port_id`.
+    pub fn port_edges(&self, port_id: &StandardId) -> &[StandardId] {
+        self.port_edges
+            .get(port_id)
+            .map(|edge_ids| edge_ids.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Ids of the connected edges ending at an input of the node with `node_id`.
+    pub fn incoming_edges(&self, node_id: &StandardId) -> Vec<&StandardId> {
+        self.attached_edges(node_id, |(_, to)| to.as_ref())
+    }
+
+    /// Ids of the connected edges starting at an output of the node with `node_id`.
+    pub fn outgoing_edges(&self, node_id: &StandardId) -> Vec<&StandardId> {
+        self.attached_edges(node_id, |(from, _)| from.as_ref())
+    }
+
+    fn attached_edges(
+        &self,
+        node_id: &StandardId,
+        end: impl Fn(&(Option<StandardId>, Option<StandardId>)) -> Option<&StandardId>,
+    ) -> Vec<&StandardId> {
+        let mut edge_ids = Vec::new();
+        for port in self.node_ports.get(node_id).into_iter().flatten() {
+            for edge_id in self.port_edges(port) {
+                let is_end = self.edge_ports.get(edge_id).and_then(&end) == Some(port);
+                if is_end && self.edge_nodes(edge_id).is_some() {
+                    edge_ids.push(edge_id);
+                }
+            }
+        }
+        edge_ids
+    }
+
+    /// Ids of the nodes feeding the node with `node_id` directly.
+    pub fn upstream_nodes(&self, node_id: &StandardId) -> Vec<&StandardId> {
+        let mut node_ids = self
+            .incoming_edges(node_id)
+            .into_iter()
+            .filter_map(|edge_id| self.edge_nodes(edge_id).map(|(from, _)| from))
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        node_ids.retain(|id| seen.insert(*id));
+        node_ids
+    }
+
+    /// Ids of the nodes fed by the node with `node_id` directly.
+    pub fn downstream_nodes(&self, node_id: &StandardId) -> Vec<&StandardId> {
+        let mut node_ids = self
+            .outgoing_edges(node_id)
+            .into_iter()
+            .filter_map(|edge_id| self.edge_nodes(edge_id).map(|(_, to)| to))
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        node_ids.retain(|id| seen.insert(*id));
+        node_ids
+    }
+
+    /// Ids of all nodes the node with `node_id` depends on, excluding itself
+    /// unless it is part of a cycle.
+    pub fn upstream_closure(&self, node_id: &StandardId) -> HashSet<&StandardId> {
+        self.closure(node_id, |id| self.upstream_nodes(id))
+    }
+
+    /// Ids of all nodes depending on the node with `node_id`, excluding itself
+    /// unless it is part of a cycle.
+    pub fn downstream_closure(&self, node_id: &StandardId) -> HashSet<&StandardId> {
+        self.closure(node_id, |id| self.downstream_nodes(id))
+    }
+
+    fn closure<'a>(
+        &'a self,
+        node_id: &StandardId,
+        next: impl Fn(&StandardId) -> Vec<&'a StandardId>,
+    ) -> HashSet<&'a StandardId> {
+        let mut visited = HashSet::new();
+        let mut stack = next(node_id);
+        while let Some(id) = stack.pop() {
+            if visited.insert(id) {
+                stack.extend(next(id));
+            }
+        }
+        visited
+    }
+
+    /// Shortest path along the edges from the node with `from` to the node with `to`.
+    pub fn shortest_path(&self, from: &StandardId, to: &StandardId) -> Option<GraphPath> {
+        // edge each node was reached through
+        let mut reached_by = HashMap::<&StandardId, Option<&StandardId>>::new();
+        let from = self.node_ports.get_key_value(from)?.0;
+        reached_by.insert(from, None);
+        let mut queue = VecDeque::from([from]);
+        while let Some(id) = queue.pop_front() {
+            if id == to {
+                let mut path = GraphPath::default();
+                let mut current = id;
+                path.nodes.push(current.clone());
+                while let Some(Some(edge_id)) = reached_by.get(current) {
+                    path.edges.push((*edge_id).clone());
+                    current = self.edge_nodes(edge_id)?.0;
+                    path.nodes.push(current.clone());
+                }
+                path.nodes.reverse();
+                path.edges.reverse();
+                return Some(path);
+            }
+            for edge_id in self.outgoing_edges(id) {
+                if let Some((_, next)) = self.edge_nodes(edge_id) {
+                    if !reached_by.contains_key(next) {
+                        reached_by.insert(next, Some(edge_id));
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// What to emphasize for `mode`. The hovered node takes precedence over
+    /// the selection. Of a single selected node the closures are highlighted,
+    /// of two selected nodes the path between them.
+    pub fn highlight(
+        &self,
+        mode: HighlightMode,
+        hovered: Option<&StandardId>,
+        selection: &[StandardId],
+    ) -> Option<Highlight> {
+        if mode == HighlightMode::Off {
+            return None;
+        }
+        // the selection may contain comments
+        let selected = selection
+            .iter()
+            .filter(|id| self.node_ports.contains_key(*id))
+            .collect::<Vec<_>>();
+        let hovered = hovered.filter(|id| self.node_ports.contains_key(*id));
+        let focus = match (hovered, selected.as_slice()) {
+            (Some(id), _) => Some(id),
+            (None, [id]) => Some(*id),
+            _ => None,
+        };
+        if let Some(id) = focus {
+            return Some(self.closure_highlight(mode, id));
+        }
+        match selected.as_slice() {
+            [a, b] => {
+                let path = self
+                    .shortest_path(a, b)
+                    .or_else(|| self.shortest_path(b, a))
+                    .unwrap_or_else(|| GraphPath {
+                        nodes: vec![(*a).clone(), (*b).clone()],
+                        edges: Vec::new(),
+                    });
+                Some(Highlight {
+                    nodes: path.nodes.into_iter().collect(),
+                    edges: path.edges.into_iter().collect(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn closure_highlight(&self, mode: HighlightMode, node_id: &StandardId) -> Highlight {
+        let mut highlight = Highlight::default();
+        highlight.nodes.insert(node_id.clone());
+        if mode.upstream() {
+            let upstream = self.upstream_closure(node_id);
+            for id in upstream.iter().copied().chain([node_id]) {
+                for edge_id in self.incoming_edges(id) {
+                    if let Some((from, _)) = self.edge_nodes(edge_id) {
+                        if upstream.contains(from) {
+                            highlight.edges.insert(edge_id.clone());
+                        }
+                    }
+                }
+            }
+            highlight.nodes.extend(upstream.into_iter().cloned());
+        }
+        if mode.downstream() {
+            let downstream = self.downstream_closure(node_id);
+            for id in downstream.iter().copied().chain([node_id]) {
+                for edge_id in self.outgoing_edges(id) {
+                    if let Some((_, to)) = self.edge_nodes(edge_id) {
+                        if downstream.contains(to) {
+                            highlight.edges.insert(edge_id.clone());
+                        }
+                    }
+                }
+            }
+            highlight.nodes.extend(downstream.into_iter().cloned());
+        }
+        highlight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::node::models::{NodeInput, NodeOutput};
+
+    fn node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            kind: None,
+            title: id.to_string(),
+            x: 0.,
+            y: 0.,
+            color: "#000000".to_string(),
+            inputs: vec![NodeInput::new(format!("{}-in", id))],
+            outputs: vec![NodeOutput::new(format!("{}-out", id))],
+            subflow: None,
+            collapsed: false,
+            fields: Vec::new(),
+            values: Default::default(),
+            status: Default::default(),
+            progress: None,
+        }
+    }
+
+    fn edge(from: &str, to: &str) -> Edge {
+        Edge {
+            id: format!("{}-{}", from, to),
+            from_output: Some(format!("{}-out", from)),
+            to_input: Some(format!("{}-in", to)),
+            ..Default::default()
+        }
+    }
+
+    fn values(node_ids: &[&str], edges: &[(&str, &str)]) -> YewFlowValues {
+        YewFlowValues {
+            nodes: node_ids.iter().map(|id| node(id)).collect(),
+            edges: edges.iter().map(|(from, to)| edge(from, to)).collect(),
+            groups: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    fn ids(nodes: &[&Node]) -> Vec<String> {
+        nodes.iter().map(|node| node.id.clone()).collect()
+    }
+
+    #[test]
+    fn topological_order_puts_feeding_nodes_first() {
+        let values = values(
+            &["d", "c", "b", "a"],
+            &[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d")],
+        );
+        let (order, cyclic) = values.graph().topological_order();
+        let order = ids(&order);
+        let position = |id: &str| order.iter().position(|other| other == id).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(cyclic.is_empty());
+        for (from, to) in [("a", "b"), ("a", "c"), ("b", "d"), ("c", "d")] {
+            assert!(position(from) < position(to));
+        }
+    }
+
+    #[test]
+    fn topological_order_separates_cycles_and_their_downstream() {
+        let values = values(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("b", "c"), ("c", "b"), ("c", "d")],
+        );
+        let (order, cyclic) = values.graph().topological_order();
+        assert_eq!(ids(&order), vec!["a"]);
+        assert_eq!(ids(&cyclic), vec!["b", "c", "d"]);
+    }
+
+    #[test]
+    fn tarjan_finds_strongly_connected_components() {
+        let values = values(
+            &["a", "b", "c", "d", "e", "f"],
+            &[
+                ("a", "b"),
+                ("b", "c"),
+                ("c", "a"),
+                ("c", "d"),
+                ("d", "e"),
+                ("e", "d"),
+                ("e", "f"),
+                ("f", "f"),
+            ],
+        );
+        let components = values
+            .graph()
+            .strongly_connected_components()
+            .into_iter()
+            .map(|component| {
+                let mut ids = ids(&component);
+                ids.sort();
+                ids
+            })
+            .collect::<Vec<_>>();
+        // downstream first
+        assert_eq!(
+            components,
+            vec![vec!["f"], vec!["d", "e"], vec!["a", "b", "c"]]
+        );
+    }
+
+    #[test]
+    fn tarjan_handles_long_chains() {
+        let node_ids = (0..10_000).map(|i| i.to_string()).collect::<Vec<_>>();
+        let node_ids = node_ids.iter().map(String::as_str).collect::<Vec<_>>();
+        let edges = node_ids
+            .windows(2)
+            .map(|w| (w[0], w[1]))
+            .collect::<Vec<_>>();
+        let values = values(&node_ids, &edges);
+        let graph = values.graph();
+        assert_eq!(graph.strongly_connected_components().len(), 10_000);
+        assert!(graph.cycle_edges().is_empty());
+    }
+
+    #[test]
+    fn cycle_edges_are_the_edges_inside_cycles() {
+        let values = values(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("b", "c"), ("c", "b"), ("c", "d"), ("d", "d")],
+        );
+        let mut edge_ids = values
+            .graph()
+            .cycle_edges()
+            .into_iter()
+            .map(|edge| edge.id.clone())
+            .collect::<Vec<_>>();
+        edge_ids.sort();
+        assert_eq!(edge_ids, vec!["b-c", "c-b", "d-d"]);
+    }
+
+    #[test]
+    fn index_updates_match_a_fresh_build() {
+        let mut values = values(&["a", "b", "c"], &[("a", "b"), ("a", "c")]);
+        let mut index = GraphIndex::new(&values.nodes, &values.edges);
+
+        // reconnect a-b to c, then add and remove nodes and edges
+        values.edges[0].to_input = Some("c-in".to_string());
+        index.insert_edge(&values.edges[0]);
+        assert_eq!(index, GraphIndex::new(&values.nodes, &values.edges));
+
+        values.nodes.push(node("d"));
+        values.edges.push(edge("c", "d"));
+        index.insert_node(&values.nodes[3]);
+        index.insert_edge(&values.edges[2]);
+        assert_eq!(index, GraphIndex::new(&values.nodes, &values.edges));
+        assert_eq!(index.downstream_nodes(&"c".to_string()), vec!["d"]);
+
+        values.nodes[3]
+            .inputs
+            .push(NodeInput::new("d-in-2".to_string()));
+        index.insert_node(&values.nodes[3]);
+        assert_eq!(index, GraphIndex::new(&values.nodes, &values.edges));
+
+        let removed = values.edges.remove(1);
+        index.remove_edge(&removed.id);
+        let removed = values.nodes.remove(0);
+        index.remove_node(&removed.id);
+        assert_eq!(index, GraphIndex::new(&values.nodes, &values.edges));
+        assert_eq!(index.edge_nodes(&"a-b".to_string()), None);
+        assert_eq!(index.port_edges(&"a-out".to_string()), ["a-b"]);
+    }
+}
@@ -1,7 +1,16 @@
+pub mod clipboard;
 pub mod components;
 pub mod constants;
+pub mod eval;
+pub mod exec;
+pub mod format;
+pub mod graph;
 pub mod store;
+pub mod subflow;
+pub mod validate;
 pub mod workspace;
 pub mod types;
+pub(crate) mod utils;
 
+pub use components::node::node_palette::NodePalette;
 pub use workspace::Workspace;
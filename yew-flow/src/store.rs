@@ -1,15 +1,25 @@
-use std::{cell::RefCell, fmt::Display, rc::Rc};
+use std::{cell::RefCell, cmp::Ordering, fmt::Display, rc::Rc};
 
 use web_sys::Element;
 use yew::prelude::*;
 
 use crate::{
+    clipboard::{copy_subgraph, regenerate_ids},
     components::{
-        edge::models::Edge,
-        node::models::{Node, NodeInput, NodeOutput},
-        viewport::models::Viewport,
+        comment::models::Comment,
+        context_menu::models::{ContextMenu, ContextMenuTarget},
+        edge::models::{Edge, EdgeActivity},
+        group::models::Group,
+        node::models::{FieldValue, Node, NodeInput, NodeOutput, NodeStatus, NodeTemplate},
+        quick_add::models::QuickAdd,
+        viewport::models::{Viewport, ViewportTransform},
     },
-    constants::{NODE_HEIGHT, NODE_WIDTH},
+    constants::{
+        COMMENT_MIN_SIZE, GROUP_HEADER_HEIGHT, GROUP_PADDING, NODE_HEIGHT, NODE_WIDTH,
+        PASTE_OFFSET, PORT_SIZE,
+    },
+    graph::GraphIndex,
+    subflow::{close_subflow_level, collapse_to_subflow, SubflowLevel},
     types::{
         standard_id::{IdentifierExt, StandardId},
         standard_unit::StandardUnit,
@@ -48,6 +58,27 @@ pub struct NewEdgeDragDeactivateCmd {
     pub to_reference: Option<NodeRef>,
     /// Type of to connector
     pub to_connector: Option<Connector>,
+    /// Open the quick-add popup when a new edge is dropped on empty canvas.
+    pub open_quick_add: bool,
+}
+
+/// # Edge End
+///
+/// One of the two ends of an edge.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdgeEnd {
+    /// End attached to the `from_output` connector.
+    From,
+    /// End attached to the `to_input` connector.
+    To,
+}
+
+#[derive(Debug)]
+pub struct EdgeUpdateActivateCmd {
+    /// Id of the edge to be updated.
+    pub edge_id: StandardId,
+    /// End of the edge to be detached.
+    pub end: EdgeEnd,
 }
 
 #[derive(Debug)]
@@ -56,6 +87,156 @@ pub struct DragEdgeCmd {
     pub x: StandardUnit,
     // y cord to which dragged edge is ending.
     pub y: StandardUnit,
+    /// Radius within which the closest compatible connector is snapped to.
+    pub connection_radius: StandardUnit,
+}
+
+/// # Pan Command
+///
+/// Pointer position relative to the viewport while panning.
+#[derive(Debug)]
+pub struct PanCmd {
+    pub x: StandardUnit,
+    pub y: StandardUnit,
+}
+
+/// # Pinch Command
+///
+/// Two finger pinch gesture state, relative to the viewport.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinchCmd {
+    /// Distance between the two pointers.
+    pub distance: StandardUnit,
+    /// Center x between the two pointers.
+    pub center_x: StandardUnit,
+    /// Center y between the two pointers.
+    pub center_y: StandardUnit,
+}
+
+/// # Zoom Command
+///
+/// Zoom by `factor` around the point (`x`, `y`) relative to the viewport.
+#[derive(Debug)]
+pub struct ZoomCmd {
+    pub factor: StandardUnit,
+    pub x: StandardUnit,
+    pub y: StandardUnit,
+}
+
+#[derive(Debug)]
+pub struct NodeSelectCmd {
+    /// Id of the node to select.
+    pub id: StandardId,
+    /// Toggle the node in the current selection instead of replacing it.
+    pub additive: bool,
+}
+
+#[derive(Debug)]
+pub struct NodesMoveCmd {
+    /// Ids of the nodes to move.
+    pub ids: Vec<StandardId>,
+    /// Horizontal distance to move by.
+    pub dx: StandardUnit,
+    /// Vertical distance to move by.
+    pub dy: StandardUnit,
+}
+
+/// # Edge Activity Command
+///
+/// Updates the visual activity state of an edge without
+/// touching its connections or coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeActivityCmd {
+    /// Id of the edge to update.
+    pub id: StandardId,
+    /// Render the edge with moving dashes.
+    pub animated: bool,
+    /// Activity state of the edge.
+    pub activity: EdgeActivity,
+}
+
+/// # Node Status Command
+///
+/// Updates the execution state of a node without
+/// touching anything else.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeStatusCmd {
+    /// Id of the node to update.
+    pub id: StandardId,
+    pub status: NodeStatus,
+    /// Progress in percent.
+    pub progress: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct ContextMenuOpenCmd {
+    pub target: ContextMenuTarget,
+    /// Absolute x position of the pointer.
+    pub x: StandardUnit,
+    /// Absolute y position of the pointer.
+    pub y: StandardUnit,
+}
+
+#[derive(Debug)]
+pub struct GroupDragActivateCmd {
+    /// Id of the group to drag.
+    pub id: StandardId,
+    /// Absolute x position of the pointer.
+    pub x: StandardUnit,
+    /// Absolute y position of the pointer.
+    pub y: StandardUnit,
+}
+
+#[derive(Debug)]
+pub struct DragGroupCmd {
+    /// Absolute x position of the pointer.
+    pub x: StandardUnit,
+    /// Absolute y position of the pointer.
+    pub y: StandardUnit,
+}
+
+#[derive(Debug)]
+pub struct NodeFieldChangeCmd {
+    /// Id of the node.
+    pub id: StandardId,
+    /// Key of the edited field.
+    pub key: String,
+    pub value: FieldValue,
+}
+
+#[derive(Debug)]
+pub struct InputValueChangeCmd {
+    /// Id of the input.
+    pub id: StandardId,
+    pub value: FieldValue,
+}
+
+#[derive(Debug)]
+pub struct CommentDragActivateCmd {
+    /// Id of the comment to drag.
+    pub id: StandardId,
+    /// Absolute x position of the pointer.
+    pub x: StandardUnit,
+    /// Absolute y position of the pointer.
+    pub y: StandardUnit,
+    /// Resize the comment instead of moving it.
+    pub resize: bool,
+}
+
+#[derive(Debug)]
+pub struct DragCommentCmd {
+    /// Absolute x position of the pointer.
+    pub x: StandardUnit,
+    /// Absolute y position of the pointer.
+    pub y: StandardUnit,
+}
+
+#[derive(Debug)]
+pub struct QuickAddOpenCmd {
+    /// Absolute x position of the pointer.
+    pub x: StandardUnit,
+    /// Absolute y position of the pointer.
+    pub y: StandardUnit,
 }
 
 /// # Yew Flow Workspace Action
@@ -79,11 +260,128 @@ pub enum WorkspaceAction {
     EdgeDrag(DragEdgeCmd),
     /// When new edge drag needs to be deactivated.
     NewEdgeDragDeactivate(NewEdgeDragDeactivateCmd),
+    /// When edge animation/activity states change.
+    EdgeActivityChange(Vec<EdgeActivityCmd>),
+    /// When execution states of nodes change.
+    NodeStatusChange(Vec<NodeStatusCmd>),
+    /// When edges closing a cycle need to be rejected (`true`) or allowed.
+    AcyclicChange(bool),
+    /// When a message needs to be shown to the user, e.g. why values were refused.
+    Notice(String),
+    /// When pointer enters an edge. Takes id of the edge.
+    EdgeHoverActivate(StandardId),
+    /// When pointer leaves an edge. Takes id of the edge.
+    EdgeHoverDeactivate(StandardId),
+    /// When pointer enters a node. Takes id of the node.
+    NodeHoverActivate(StandardId),
+    /// When pointer leaves a node. Takes id of the node.
+    NodeHoverDeactivate(StandardId),
+    /// When an end of an existing edge is grabbed to be reconnected.
+    EdgeUpdateActivate(EdgeUpdateActivateCmd),
+    /// When an edge being reconnected is dropped.
+    EdgeUpdateDeactivate(NewEdgeDragDeactivateCmd),
+    /// When viewport pan needs to be activated.
+    PanActivate(PanCmd),
+    /// When viewport needs to be panned.
+    Pan(PanCmd),
+    /// When viewport pan needs to be deactivated.
+    PanDeactivate,
+    /// When a two finger pinch starts. Cancels any other interaction.
+    PinchActivate(PinchCmd),
+    /// When the pinch gesture changes.
+    Pinch(PinchCmd),
+    /// When the pinch gesture ends.
+    PinchDeactivate,
+    /// When viewport needs to be zoomed.
+    Zoom(ZoomCmd),
+    /// When a node needs to be selected.
+    NodeSelect(NodeSelectCmd),
+    /// When the selection needs to be cleared.
+    SelectionClear,
+    /// When nodes need to be moved by an offset (eg. with arrow keys).
+    NodesMove(NodesMoveCmd),
+    /// When a connector is activated with the keyboard. The first activation
+    /// starts a connection, the second one completes it.
+    KeyboardConnect(Connector),
+    /// When the pending keyboard connection needs to be cancelled.
+    KeyboardConnectCancel,
+    /// When edge coordinates need to be re-read from the rendered connectors.
+    RefreshEdgePositions,
+    /// When a copied subgraph needs to be inserted. Ids are regenerated
+    /// and the pasted nodes become the selection.
+    Paste(YewFlowValues),
+    /// When the selected nodes need to be duplicated.
+    DuplicateSelection,
+    /// When a new node needs to be added. The node becomes the selection.
+    AddNode(Node),
+    /// When nodes need to be deleted together with their edges.
+    NodesDelete(Vec<StandardId>),
+    /// When edges need to be deleted.
+    EdgesDelete(Vec<StandardId>),
+    /// When a context menu needs to be opened.
+    ContextMenuOpen(ContextMenuOpenCmd),
+    /// When the context menu needs to be closed.
+    ContextMenuClose,
+    /// When the quick-add popup needs to be opened.
+    QuickAddOpen(QuickAddOpenCmd),
+    /// When the quick-add popup needs to be closed.
+    QuickAddClose,
+    /// When a template is chosen in the quick-add popup. Inserts the node
+    /// and connects it to the connector the popup was opened from.
+    QuickAddSelect(NodeTemplate),
+    /// When a group header is grabbed.
+    GroupDragActivate(GroupDragActivateCmd),
+    /// When a group and its children need to be dragged.
+    GroupDrag(DragGroupCmd),
+    /// When group drag needs to be deactivated.
+    GroupDragDeactivate,
+    /// When a group needs to be collapsed/expanded. Takes id of the group.
+    GroupToggleCollapse(StandardId),
+    /// When the selected nodes need to be put into a new group.
+    GroupSelection,
+    /// When a new comment needs to be added.
+    AddComment(Comment),
+    /// When the text/appearance of a comment changed.
+    CommentUpdate(Comment),
+    /// When a comment is grabbed to be moved or resized.
+    CommentDragActivate(CommentDragActivateCmd),
+    /// When a comment needs to be moved/resized.
+    CommentDrag(DragCommentCmd),
+    /// When comment drag needs to be deactivated.
+    CommentDragDeactivate,
+    /// When a node needs to be collapsed/expanded. Takes id of the node.
+    NodeToggleCollapse(StandardId),
+    /// When a field of a node is edited.
+    NodeFieldChange(NodeFieldChangeCmd),
+    /// When the inline value of an unconnected input is edited.
+    InputValueChange(InputValueChangeCmd),
+    /// When the selected nodes need to be collapsed into a subflow node.
+    SubflowCreate,
+    /// When the inner graph of a subflow node needs to be opened.
+    /// Takes id of the subflow node.
+    SubflowEnter(StandardId),
+    /// When the workspace needs to go back up to a parent level.
+    /// Takes the depth to go back to, `0` being the root flow.
+    SubflowExit(usize),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct NewEdgeDragMode {
     pub from_connector: Connector,
+    /// Closest compatible connector within the connection radius.
+    pub snap_target: Option<Connector>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeUpdateMode {
+    /// Id of the edge being reconnected.
+    pub edge_id: StandardId,
+    /// End of the edge being dragged.
+    pub end: EdgeEnd,
+    /// Edge before it was detached. Restored on an invalid drop.
+    pub original: Edge,
+    /// Closest compatible connector within the connection radius.
+    pub snap_target: Option<Connector>,
 }
 
 /// # User Interaction Mode
@@ -98,6 +396,22 @@ pub enum InteractionMode {
     NodeDrag(StandardId),
     /// New Edge drag mode.
     NewEdgeDrag(NewEdgeDragMode),
+    /// Edge hover mode. Pass `edge_id` of edge being hovered.
+    EdgeHover(StandardId),
+    /// Edge update mode. An end of an existing edge is being dragged.
+    EdgeUpdate(EdgeUpdateMode),
+    /// Viewport pan mode. Pass last pointer position relative to the viewport.
+    Pan(StandardUnit, StandardUnit),
+    /// Two finger pinch zoom mode. Pass last pinch state.
+    Pinch(PinchCmd),
+    /// Keyboard connection mode. Pass the connector the connection starts from.
+    KeyboardConnect(Connector),
+    /// Group drag mode. Pass `group_id` and last pointer position in flow coordinates.
+    GroupDrag(StandardId, StandardUnit, StandardUnit),
+    /// Comment drag mode. Pass `comment_id` and last pointer position in flow coordinates.
+    CommentDrag(StandardId, StandardUnit, StandardUnit),
+    /// Comment resize mode. Pass `comment_id` and last pointer position in flow coordinates.
+    CommentResize(StandardId, StandardUnit, StandardUnit),
 }
 
 impl Default for InteractionMode {
@@ -106,6 +420,289 @@ impl Default for InteractionMode {
     }
 }
 
+/// # Connection Error
+///
+/// Reasons for rejecting a connection between two connectors.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionError {
+    /// Both connectors are inputs or both are outputs.
+    SameKind,
+    /// Both connectors belong to the same node.
+    SameNode,
+    /// An edge between the connectors already exists.
+    AlreadyConnected,
+    /// The edge would close a cycle through the nodes with these titles.
+    Cycle(Vec<String>),
+}
+
+impl Display for ConnectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionError::SameKind => write!(f, "can only connect an output to an input"),
+            ConnectionError::SameNode => write!(f, "cannot connect a node to itself"),
+            ConnectionError::AlreadyConnected => write!(f, "connectors are already connected"),
+            ConnectionError::Cycle(titles) => {
+                write!(f, "it would create a cycle {}", titles.join(" → "))
+            }
+        }
+    }
+}
+
+/// # Validate Connection
+///
+/// Check if an edge can connect connectors `a` and `b`.
+///
+/// - `ignore_edge` is skipped while looking for existing connections.
+pub fn validate_connection(
+    nodes: &[Node],
+    edges: &[Edge],
+    a: &Connector,
+    b: &Connector,
+    ignore_edge: Option<&StandardId>,
+) -> Result<(), ConnectionError> {
+    let (output, input) = match (a, b) {
+        (Connector::Output(output), Connector::Input(input))
+        | (Connector::Input(input), Connector::Output(output)) => (output, input),
+        _ => return Err(ConnectionError::SameKind),
+    };
+    let output_node = nodes
+        .iter()
+        .find(|node| node.outputs.iter().any(|o| o.id == *output));
+    let input_node = nodes
+        .iter()
+        .find(|node| node.inputs.iter().any(|i| i.id == *input));
+    if let (Some(output_node), Some(input_node)) = (output_node, input_node) {
+        if output_node.id == input_node.id {
+            return Err(ConnectionError::SameNode);
+        }
+    }
+    let already_connected = edges.iter().any(|edge| {
+        Some(&edge.id) != ignore_edge
+            && edge.from_output.as_ref() == Some(output)
+            && edge.to_input.as_ref() == Some(input)
+    });
+    if already_connected {
+        return Err(ConnectionError::AlreadyConnected);
+    }
+    Ok(())
+}
+
+/// # Validate Acyclic
+///
+/// Check that an edge between connectors `a` and `b` would not close a cycle.
+pub fn validate_acyclic(
+    graph: &GraphIndex,
+    nodes: &[Node],
+    a: &Connector,
+    b: &Connector,
+) -> Result<(), ConnectionError> {
+    let (output, input) = match (a, b) {
+        (Connector::Output(output), Connector::Input(input))
+        | (Connector::Input(input), Connector::Output(output)) => (output, input),
+        _ => return Err(ConnectionError::SameKind),
+    };
+    let from = graph.node_of_port(output);
+    let to = graph.node_of_port(input);
+    let path = from
+        .zip(to)
+        .and_then(|(from, to)| graph.shortest_path(to, from));
+    match path {
+        Some(path) => {
+            let title = |id: &StandardId| {
+                nodes
+                    .iter()
+                    .find(|node| node.id == *id)
+                    .map(|node| node.title.clone())
+                    .unwrap_or_default()
+            };
+            let mut titles = path.nodes.iter().map(title).collect::<Vec<_>>();
+            // the new edge leads back to the first node
+            titles.extend(path.nodes.first().map(title));
+            Err(ConnectionError::Cycle(titles))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Get position of a connector element relative to the viewport.
+pub fn connector_position(
+    viewport: &Viewport,
+    reference: &NodeRef,
+) -> Option<(StandardUnit, StandardUnit)> {
+    let elm = reference.cast::<Element>()?;
+    let x = elm.get_bounding_client_rect().x();
+    let y = elm.get_bounding_client_rect().y();
+    Some((
+        viewport.relative_x_pos_from_abs(x, None),
+        viewport.relative_y_pos_from_abs(y, None),
+    ))
+}
+
+/// Get the connector at the end of an edge which stays attached
+/// while the other `end` is being reconnected.
+fn fixed_connector(end: &EdgeEnd, edge: &Edge) -> Option<Connector> {
+    match end {
+        EdgeEnd::From => edge.to_input.clone().map(Connector::Input),
+        EdgeEnd::To => edge.from_output.clone().map(Connector::Output),
+    }
+}
+
+/// Update the coordinates of all edges connected to `node`
+/// from the positions of its connector elements.
+pub fn update_node_edge_positions(viewport: &Viewport, node: &Node, edges: &mut [Edge]) {
+    for output in node.outputs.iter() {
+        if let Some((x, y)) = connector_position(viewport, &output.reference) {
+            edges
+                .iter_mut()
+                .filter(|edge| edge.from_output.as_ref() == Some(&output.id))
+                .for_each(|edge| {
+                    edge.x1 = x;
+                    edge.y1 = y;
+                });
+        }
+    }
+    for input in node.inputs.iter() {
+        if let Some((x, y)) = connector_position(viewport, &input.reference) {
+            edges
+                .iter_mut()
+                .filter(|edge| edge.to_input.as_ref() == Some(&input.id))
+                .for_each(|edge| {
+                    edge.x2 = x;
+                    edge.y2 = y;
+                });
+        }
+    }
+}
+
+/// Make `node` a child of the top most group containing it
+/// and remove it from all other groups.
+pub fn update_group_membership(groups: &mut [Group], node: &Node) {
+    let parent = groups.iter().rposition(|group| group.contains(node));
+    for (index, group) in groups.iter_mut().enumerate() {
+        group.children.retain(|id| *id != node.id);
+        if parent == Some(index) {
+            group.children.push(node.id.clone());
+        }
+    }
+}
+
+/// Ids of the edges connected to any of the connectors with `port_ids`.
+pub fn attached_edge_ids(edges: &[Edge], port_ids: &[StandardId]) -> Vec<StandardId> {
+    edges
+        .iter()
+        .filter(|edge| {
+            [&edge.from_output, &edge.to_input]
+                .into_iter()
+                .flatten()
+                .any(|id| port_ids.contains(id))
+        })
+        .map(|edge| edge.id.clone())
+        .collect()
+}
+
+/// Human readable label of a connector. Used for accessibility.
+pub fn connector_label(nodes: &[Node], connector: &Connector) -> String {
+    nodes
+        .iter()
+        .find_map(|node| {
+            let (kind, index, label) = match connector {
+                Connector::Input(id) => {
+                    let index = node.inputs.iter().position(|i| i.id == *id)?;
+                    ("input", index, &node.inputs[index].label)
+                }
+                Connector::Output(id) => {
+                    let index = node.outputs.iter().position(|o| o.id == *id)?;
+                    ("output", index, &node.outputs[index].label)
+                }
+            };
+            Some(match label {
+                Some(label) => format!("{} {} of {}", kind, label, node.title),
+                None => format!("{} {} of {}", kind, index + 1, node.title),
+            })
+        })
+        .unwrap_or_else(|| "unknown connector".to_string())
+}
+
+/// Get the element reference of a connector.
+pub fn connector_reference(nodes: &[Node], connector: &Connector) -> Option<NodeRef> {
+    nodes.iter().find_map(|node| match connector {
+        Connector::Input(id) => node
+            .inputs
+            .iter()
+            .find(|input| input.id == *id)
+            .map(|input| input.reference.clone()),
+        Connector::Output(id) => node
+            .outputs
+            .iter()
+            .find(|output| output.id == *id)
+            .map(|output| output.reference.clone()),
+    })
+}
+
+/// # Find Snap Target
+///
+/// Find the closest connector to (`x`, `y`) within `radius`
+/// which can be connected to `from_connector`. The connector under
+/// the pointer is always in reach, even with a radius of `0`.
+pub fn find_snap_target(
+    viewport: &Viewport,
+    nodes: &[Node],
+    edges: &[Edge],
+    from_connector: &Connector,
+    (x, y): (StandardUnit, StandardUnit),
+    radius: StandardUnit,
+    ignore_edge: Option<&StandardId>,
+) -> Option<Connector> {
+    nodes
+        .iter()
+        .flat_map(|node| {
+            let inputs = node
+                .inputs
+                .iter()
+                .map(|input| (Connector::Input(input.id.clone()), &input.reference));
+            let outputs = node
+                .outputs
+                .iter()
+                .map(|output| (Connector::Output(output.id.clone()), &output.reference));
+            inputs.chain(outputs)
+        })
+        .filter_map(|(connector, reference)| {
+            let (cx, cy) = connector_position(viewport, reference)?;
+            // measure from the center of the connector
+            let distance = (cx + PORT_SIZE / 2. - x).hypot(cy + PORT_SIZE / 2. - y);
+            Some((connector, distance))
+        })
+        .filter(|(connector, distance)| {
+            *distance <= radius.max(PORT_SIZE / 2.)
+                && validate_connection(nodes, edges, from_connector, connector, ignore_edge).is_ok()
+        })
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .map(|(connector, _)| connector)
+}
+
+/// # Resolve Drop Target
+///
+/// Pick the connector a dragged edge end should connect to.
+/// Prefers the dropped on connector, then falls back to the snap target.
+fn resolve_drop_target(
+    nodes: &[Node],
+    edges: &[Edge],
+    from_connector: &Connector,
+    dropped: Option<(NodeRef, Connector)>,
+    snap_target: Option<Connector>,
+    ignore_edge: Option<&StandardId>,
+) -> Option<(NodeRef, Connector)> {
+    let snapped = snap_target.and_then(|connector| {
+        connector_reference(nodes, &connector).map(|reference| (reference, connector))
+    });
+    dropped
+        .into_iter()
+        .chain(snapped)
+        .find(|(_, to_connector)| {
+            validate_connection(nodes, edges, from_connector, to_connector, ignore_edge).is_ok()
+        })
+}
+
 /// # Yew Flow Workspace Store
 ///
 /// Main state/store for `yew-flow`.
@@ -115,6 +712,29 @@ pub struct WorkspaceStore {
     pub nodes: Vec<Node>,
     pub edges: Vec<Edge>,
     pub interaction_mode: InteractionMode,
+    /// Ids of the selected nodes and comments.
+    pub selection: Vec<StandardId>,
+    /// Latest message for screen readers, rendered in a live region.
+    pub announcement: String,
+    /// Currently open context menu.
+    pub context_menu: Option<ContextMenu>,
+    /// Currently open quick-add popup.
+    pub quick_add: Option<QuickAdd>,
+    /// Groups/frames rendered beneath the nodes.
+    pub groups: Vec<Group>,
+    /// Parent levels of the open subflow, empty when the root flow is open.
+    pub subflow_path: Vec<SubflowLevel>,
+    /// Comments/sticky notes on the canvas.
+    pub comments: Vec<Comment>,
+    /// Reject edges that would close a cycle.
+    pub acyclic: bool,
+    /// Connections between the nodes, updated by the actions that add,
+    /// reconnect or remove nodes and edges.
+    pub graph: GraphIndex,
+    /// Id of the node under the pointer.
+    pub hovered_node: Option<StandardId>,
+    /// Why the last interaction was rejected. Shown until the next interaction starts.
+    pub notice: Option<String>,
 }
 
 impl WorkspaceStore {
@@ -129,44 +749,73 @@ impl WorkspaceStore {
                     let id = auto_incr_id.clone().borrow_mut().next().unwrap();
                     Node {
                         id: StandardId::generate(),
+                        kind: None,
                         title: format!("Node {}", id),
                         x: ((NODE_WIDTH + 10.) * i as f64) as StandardUnit,
                         y: ((NODE_HEIGHT + 10.) * j as f64) as StandardUnit,
                         color: "#ff0000".to_string(),
                         inputs: (0..3)
                             .into_iter()
-                            .map(|input| NodeInput {
-                                id: format!("node-{}--input-{}", id, input),
-                                reference: NodeRef::default(),
-                            })
+                            .map(|input| NodeInput::new(format!("node-{}--input-{}", id, input)))
                             .collect(),
                         outputs: (0..3)
                             .into_iter()
-                            .map(|output| NodeOutput {
-                                id: format!("node-{}--output-{}", id, output),
-                                reference: NodeRef::default(),
+                            .map(|output| {
+                                NodeOutput::new(format!("node-{}--output-{}", id, output))
                             })
                             .collect(),
+                        subflow: None,
+                        collapsed: false,
+                        fields: Vec::new(),
+                        values: Default::default(),
+                        status: Default::default(),
+                        progress: None,
                     }
                 })
             })
             .flatten()
             .collect::<Vec<Node>>();
+        let edges = (0..2)
+            .into_iter()
+            .map(|i| Edge {
+                id: StandardId::generate(),
+                from_output: Some(format!("node-{}--output-{}", i, 0)),
+                to_input: Some(format!("node-{}--input-{}", i + 1, 0)),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
         Self {
             viewport: None,
+            graph: GraphIndex::new(&nodes, &edges),
             nodes,
-            edges: (0..2)
-                .into_iter()
-                .map(|i| Edge {
-                    id: StandardId::generate(),
-                    from_output: Some(format!("node-{}--output-{}", i, 0)),
-                    to_input: Some(format!("node-{}--input-{}", i + 1, 0)),
-                    ..Default::default()
-                })
-                .collect(),
+            edges,
             interaction_mode: InteractionMode::None,
+            selection: Default::default(),
+            announcement: Default::default(),
+            context_menu: None,
+            quick_add: None,
+            groups: Default::default(),
+            subflow_path: Default::default(),
+            comments: Default::default(),
+            acyclic: false,
+            notice: None,
+            hovered_node: None,
         }
     }
+
+    /// Values of the whole flow, including the changes
+    /// made inside the open subflow.
+    pub fn root_values(&self) -> YewFlowValues {
+        self.subflow_path.iter().rev().cloned().fold(
+            YewFlowValues {
+                nodes: self.nodes.clone(),
+                edges: self.edges.clone(),
+                groups: self.groups.clone(),
+                comments: self.comments.clone(),
+            },
+            |inner, level| close_subflow_level(level, inner),
+        )
+    }
 }
 
 impl Default for WorkspaceStore {
@@ -176,6 +825,17 @@ impl Default for WorkspaceStore {
             nodes: Default::default(),
             edges: Default::default(),
             interaction_mode: Default::default(),
+            selection: Default::default(),
+            announcement: Default::default(),
+            context_menu: None,
+            quick_add: None,
+            groups: Default::default(),
+            subflow_path: Default::default(),
+            comments: Default::default(),
+            acyclic: false,
+            notice: None,
+            graph: Default::default(),
+            hovered_node: None,
         }
     }
 }
@@ -186,68 +846,58 @@ impl Reducible for WorkspaceStore {
     fn reduce(self: std::rc::Rc<Self>, action: Self::Action) -> std::rc::Rc<Self> {
         // log::info!("current_state: {:?}", self.clone());
         // log::info!("action: {:?}", action);
-        let viewport = self.viewport.clone();
+        let mut viewport = self.viewport.clone();
         let mut nodes = self.nodes.clone();
         let mut edges = self.edges.clone();
         let mut interaction_mode = self.interaction_mode.clone();
+        let mut selection = self.selection.clone();
+        let mut announcement = self.announcement.clone();
+        let mut context_menu = self.context_menu.clone();
+        let mut quick_add = self.quick_add.clone();
+        let mut groups = self.groups.clone();
+        let mut subflow_path = self.subflow_path.clone();
+        let mut comments = self.comments.clone();
+        let mut acyclic = self.acyclic;
+        let mut notice = self.notice.clone();
+        let mut graph = self.graph.clone();
+        let mut hovered_node = self.hovered_node.clone();
+        // starting another interaction dismisses the context menu and quick-add popup
+        if matches!(
+            action,
+            WorkspaceAction::NodeDragActivate(_)
+                | WorkspaceAction::NewEdgeDragActivate(_)
+                | WorkspaceAction::EdgeUpdateActivate(_)
+                | WorkspaceAction::PanActivate(_)
+                | WorkspaceAction::PinchActivate(_)
+                | WorkspaceAction::GroupDragActivate(_)
+                | WorkspaceAction::CommentDragActivate(_)
+        ) {
+            context_menu = None;
+            quick_add = None;
+            notice = None;
+        }
         match action {
             WorkspaceAction::Init(init_values) => {
                 if let Some(init_values) = init_values {
                     nodes = init_values.nodes;
                     edges = init_values.edges;
+                    groups = init_values.groups;
+                    comments = init_values.comments;
+                    subflow_path.clear();
+                    graph = GraphIndex::new(&nodes, &edges);
                 }
-                if let Some(viewport) = viewport.clone() {
-                    nodes.iter().for_each(|node| {
-                        let Node {
-                            inputs, outputs, ..
-                        } = node;
-                        for output in outputs.iter() {
-                            edges.iter_mut().for_each(|edge| {
-                                if edge.from_output == Some(output.id.clone()) {
-                                    if let Some(elm) = output.reference.cast::<Element>() {
-                                        let x = elm.get_bounding_client_rect().x();
-                                        let y = elm.get_bounding_client_rect().y();
-                                        let x = viewport.relative_x_pos_from_abs(x, None);
-                                        let y = viewport.relative_y_pos_from_abs(y, None);
-                                        edge.x1 = x;
-                                        edge.y1 = y;
-                                    }
-                                }
-                            });
-                        }
-                        for input in inputs.iter() {
-                            edges.iter_mut().for_each(|edge| {
-                                if edge.to_input == Some(input.id.clone()) {
-                                    if let Some(elm) = input.reference.cast::<Element>() {
-                                        let x = elm.get_bounding_client_rect().x();
-                                        let y = elm.get_bounding_client_rect().y();
-                                        let x = viewport.relative_x_pos_from_abs(x, None);
-                                        let y = viewport.relative_y_pos_from_abs(y, None);
-                                        edge.x2 = x;
-                                        edge.y2 = y;
-                                    }
-                                }
-                            });
-                        }
-                    });
-                }
-                Self {
-                    viewport,
-                    nodes,
-                    edges,
-                    interaction_mode,
+                if let Some(ref viewport) = viewport {
+                    for node in nodes.iter() {
+                        update_node_edge_positions(viewport, node, &mut edges);
+                    }
                 }
-                .into()
             }
-            WorkspaceAction::ViewPortChange(viewport) => {
-                let viewport = Some(viewport);
-                Self {
-                    viewport,
-                    nodes,
-                    edges,
-                    interaction_mode,
-                }
-                .into()
+            WorkspaceAction::ViewPortChange(new_viewport) => {
+                // keep pan/zoom of the previous viewport
+                let transform = viewport
+                    .map(|viewport| viewport.transform)
+                    .unwrap_or_default();
+                viewport = Some(new_viewport.with_transform(transform));
             }
             WorkspaceAction::NodeDrag(DragNodeCmd { x, y }) => {
                 if let InteractionMode::NodeDrag(ref id) = interaction_mode {
@@ -255,68 +905,23 @@ impl Reducible for WorkspaceStore {
                     if let Some(active_node) = active_node {
                         active_node.x = x; // assign new coord values
                         active_node.y = y;
-                        let Node {
-                            inputs, outputs, ..
-                        } = active_node;
                         if let Some(ref viewport) = viewport {
-                            for output in outputs.iter() {
-                                edges.iter_mut().for_each(|edge| {
-                                    if edge.from_output == Some(output.id.clone()) {
-                                        if let Some(elm) = output.reference.cast::<Element>() {
-                                            let x = elm.get_bounding_client_rect().x();
-                                            let y = elm.get_bounding_client_rect().y();
-                                            let x = viewport.relative_x_pos_from_abs(x, None);
-                                            let y = viewport.relative_y_pos_from_abs(y, None);
-                                            edge.x1 = x;
-                                            edge.y1 = y;
-                                        }
-                                    }
-                                });
-                            }
-                            for input in inputs.iter() {
-                                edges.iter_mut().for_each(|edge| {
-                                    if edge.to_input == Some(input.id.clone()) {
-                                        if let Some(elm) = input.reference.cast::<Element>() {
-                                            let x = elm.get_bounding_client_rect().x();
-                                            let y = elm.get_bounding_client_rect().y();
-                                            let x = viewport.relative_x_pos_from_abs(x, None);
-                                            let y = viewport.relative_y_pos_from_abs(y, None);
-                                            edge.x2 = x;
-                                            edge.y2 = y;
-                                        }
-                                    }
-                                });
-                            }
+                            update_node_edge_positions(viewport, active_node, &mut edges);
                         }
                     }
                 }
-                Self {
-                    viewport,
-                    nodes,
-                    edges,
-                    interaction_mode,
-                }
-                .into()
             }
             WorkspaceAction::NodeDragActivate(id) => {
                 interaction_mode = InteractionMode::NodeDrag(id);
-                Self {
-                    viewport,
-                    nodes,
-                    edges,
-                    interaction_mode,
-                }
-                .into()
             }
             WorkspaceAction::NodeDragDeactivate => {
-                interaction_mode = InteractionMode::None;
-                Self {
-                    viewport,
-                    nodes,
-                    edges,
-                    interaction_mode,
+                // dropping a node into/out of a group changes its membership
+                if let InteractionMode::NodeDrag(ref id) = interaction_mode {
+                    if let Some(node) = nodes.iter().find(|n| n.id == *id) {
+                        update_group_membership(&mut groups, node);
+                    }
                 }
-                .into()
+                interaction_mode = InteractionMode::None;
             }
             WorkspaceAction::NewEdgeDragActivate(NewEdgeDragActivateCmd {
                 from_reference,
@@ -324,6 +929,7 @@ impl Reducible for WorkspaceStore {
             }) => {
                 interaction_mode = InteractionMode::NewEdgeDrag(NewEdgeDragMode {
                     from_connector: from_connector.clone(),
+                    snap_target: None,
                 });
                 if let Some(elm) = from_reference.cast::<Element>() {
                     if let Some(ref viewport) = viewport {
@@ -351,92 +957,1115 @@ impl Reducible for WorkspaceStore {
                                     edge.to_input = Some(id.clone());
                                 }
                             }
+                            graph.insert_edge(&edge);
                             edges.push(edge);
                         }
                     }
                 }
-                Self {
-                    viewport,
-                    nodes,
-                    edges,
-                    interaction_mode,
-                }
-                .into()
             }
-            WorkspaceAction::EdgeDrag(DragEdgeCmd { x, y }) => {
-                if let InteractionMode::NewEdgeDrag(NewEdgeDragMode { ref from_connector }) =
-                    interaction_mode
-                {
+            WorkspaceAction::EdgeDrag(DragEdgeCmd {
+                x,
+                y,
+                connection_radius,
+            }) => match interaction_mode {
+                InteractionMode::NewEdgeDrag(NewEdgeDragMode {
+                    ref from_connector,
+                    ref mut snap_target,
+                }) => {
+                    let temp_edge_id = edges.last().map(|edge| edge.id.clone());
+                    *snap_target = viewport.as_ref().and_then(|viewport| {
+                        find_snap_target(
+                            viewport,
+                            &nodes,
+                            &edges,
+                            from_connector,
+                            (x, y),
+                            connection_radius,
+                            temp_edge_id.as_ref(),
+                        )
+                    });
                     if let Some(edge) = edges.last_mut() {
                         match from_connector {
-                            Connector::Output(id) => {
+                            Connector::Output(_) => {
                                 edge.x2 = x;
                                 edge.y2 = y;
                             }
-                            Connector::Input(id) => {
+                            Connector::Input(_) => {
                                 edge.x1 = x;
                                 edge.y1 = y;
                             }
                         }
                     }
                 }
-                Self {
-                    viewport,
-                    nodes,
-                    edges,
-                    interaction_mode,
+                InteractionMode::EdgeUpdate(EdgeUpdateMode {
+                    ref edge_id,
+                    ref end,
+                    ref original,
+                    ref mut snap_target,
+                }) => {
+                    *snap_target = match (viewport.as_ref(), fixed_connector(end, original)) {
+                        (Some(viewport), Some(fixed_connector)) => find_snap_target(
+                            viewport,
+                            &nodes,
+                            &edges,
+                            &fixed_connector,
+                            (x, y),
+                            connection_radius,
+                            Some(edge_id),
+                        ),
+                        _ => None,
+                    };
+                    if let Some(edge) = edges.iter_mut().find(|e| e.id == *edge_id) {
+                        match end {
+                            EdgeEnd::From => {
+                                edge.x1 = x;
+                                edge.y1 = y;
+                            }
+                            EdgeEnd::To => {
+                                edge.x2 = x;
+                                edge.y2 = y;
+                            }
+                        }
+                    }
                 }
-                .into()
-            }
+                _ => {}
+            },
             WorkspaceAction::NewEdgeDragDeactivate(NewEdgeDragDeactivateCmd {
                 to_reference,
                 to_connector,
+                open_quick_add,
             }) => {
-                if let Some(to_reference) = to_reference {
-                    if let (Some(ref viewport), Some(elm), Some(to_connector), Some(edge)) = (
-                        viewport.clone(),
-                        to_reference.cast::<Element>(),
-                        to_connector,
-                        edges.last_mut(),
-                    ) {
+                if let InteractionMode::NewEdgeDrag(NewEdgeDragMode {
+                    ref from_connector,
+                    ref snap_target,
+                }) = interaction_mode
+                {
+                    let temp_edge_id = edges.last().map(|edge| edge.id.clone());
+                    let mut target = resolve_drop_target(
+                        &nodes,
+                        &edges,
+                        from_connector,
+                        to_reference.zip(to_connector),
+                        snap_target.clone(),
+                        temp_edge_id.as_ref(),
+                    );
+                    let mut rejected = false;
+                    if let (true, Some((_, to_connector))) = (acyclic, &target) {
+                        if let Err(err) =
+                            validate_acyclic(&graph, &nodes, from_connector, to_connector)
+                        {
+                            announcement = format!("Cannot connect, {}", err);
+                            notice = Some(announcement.clone());
+                            target = None;
+                            rejected = true;
+                        }
+                    }
+                    let mut connected = false;
+                    if let (Some(ref viewport), Some((to_reference, to_connector)), Some(edge)) =
+                        (viewport.clone(), target, edges.last_mut())
+                    {
                         if viewport.dimensions.width > 0. && viewport.dimensions.height > 0. {
-                            let x = elm.get_bounding_client_rect().x() as StandardUnit;
-                            let y = elm.get_bounding_client_rect().y() as StandardUnit;
-                            let x = viewport.relative_x_pos_from_abs(x, None);
-                            let y = viewport.relative_y_pos_from_abs(y, None);
-                            match to_connector {
-                                Connector::Output(id) => {
-                                    edge.x1 = x;
-                                    edge.y1 = y;
-                                    edge.from_output = Some(id.clone());
+                            if let Some((x, y)) = connector_position(viewport, &to_reference) {
+                                announcement = format!(
+                                    "Connected {} to {}",
+                                    connector_label(&nodes, from_connector),
+                                    connector_label(&nodes, &to_connector),
+                                );
+                                match to_connector {
+                                    Connector::Output(id) => {
+                                        edge.x1 = x;
+                                        edge.y1 = y;
+                                        edge.from_output = Some(id.clone());
+                                    }
+                                    Connector::Input(id) => {
+                                        edge.x2 = x;
+                                        edge.y2 = y;
+                                        edge.to_input = Some(id.clone());
+                                    }
                                 }
-                                Connector::Input(id) => {
-                                    edge.x2 = x;
-                                    edge.y2 = y;
-                                    edge.to_input = Some(id.clone());
+                                graph.insert_edge(edge);
+                                connected = true;
+                            }
+                        }
+                    }
+                    if !connected {
+                        // remove the temp edge if not connected
+                        if let Some(edge) = edges.pop() {
+                            graph.remove_edge(&edge.id);
+                            if open_quick_add && !rejected {
+                                // offer to create a node at the loose end
+                                let (x, y) = match from_connector {
+                                    Connector::Output(_) => (edge.x2, edge.y2),
+                                    Connector::Input(_) => (edge.x1, edge.y1),
+                                };
+                                quick_add = Some(QuickAdd {
+                                    x,
+                                    y,
+                                    from_connector: Some(from_connector.clone()),
+                                });
+                            }
+                        }
+                    }
+                }
+                interaction_mode = InteractionMode::None; // reset interaction mode
+            }
+            WorkspaceAction::EdgeHoverActivate(id) => {
+                // only hover when not busy with another interaction
+                if interaction_mode == InteractionMode::None {
+                    interaction_mode = InteractionMode::EdgeHover(id);
+                }
+            }
+            WorkspaceAction::EdgeHoverDeactivate(id) => {
+                if interaction_mode == InteractionMode::EdgeHover(id) {
+                    interaction_mode = InteractionMode::None;
+                }
+            }
+            WorkspaceAction::NodeHoverActivate(id) => {
+                hovered_node = Some(id);
+            }
+            WorkspaceAction::NodeHoverDeactivate(id) => {
+                if hovered_node.as_ref() == Some(&id) {
+                    hovered_node = None;
+                }
+            }
+            WorkspaceAction::EdgeUpdateActivate(EdgeUpdateActivateCmd { edge_id, end }) => {
+                if let Some(edge) = edges.iter_mut().find(|e| e.id == edge_id) {
+                    let original = edge.clone();
+                    // detach the grabbed end
+                    match end {
+                        EdgeEnd::From => edge.from_output = None,
+                        EdgeEnd::To => edge.to_input = None,
+                    }
+                    graph.insert_edge(edge);
+                    interaction_mode = InteractionMode::EdgeUpdate(EdgeUpdateMode {
+                        edge_id,
+                        end,
+                        original,
+                        snap_target: None,
+                    });
+                }
+            }
+            WorkspaceAction::EdgeUpdateDeactivate(NewEdgeDragDeactivateCmd {
+                to_reference,
+                to_connector,
+                ..
+            }) => {
+                if let InteractionMode::EdgeUpdate(EdgeUpdateMode {
+                    ref edge_id,
+                    ref end,
+                    ref original,
+                    ref snap_target,
+                }) = interaction_mode
+                {
+                    let target = fixed_connector(end, original)
+                        .and_then(|fixed_connector| {
+                            let target = resolve_drop_target(
+                                &nodes,
+                                &edges,
+                                &fixed_connector,
+                                to_reference.zip(to_connector),
+                                snap_target.clone(),
+                                Some(edge_id),
+                            )?;
+                            if acyclic {
+                                if let Err(err) =
+                                    validate_acyclic(&graph, &nodes, &fixed_connector, &target.1)
+                                {
+                                    announcement = format!("Cannot reconnect, {}", err);
+                                    notice = Some(announcement.clone());
+                                    return None;
                                 }
                             }
-                        } else {
-                            // remove the temp edge if not connected
-                            edges.pop();
+                            Some(target)
+                        })
+                        .and_then(|(to_reference, to_connector)| {
+                            viewport
+                                .as_ref()
+                                .and_then(|viewport| connector_position(viewport, &to_reference))
+                                .map(|position| (position, to_connector))
+                        });
+                    if let Some((_, ref to_connector)) = target {
+                        announcement =
+                            format!("Reconnected to {}", connector_label(&nodes, to_connector));
+                    }
+                    if let Some(edge) = edges.iter_mut().find(|e| e.id == *edge_id) {
+                        match target {
+                            Some(((x, y), Connector::Output(id))) => {
+                                edge.x1 = x;
+                                edge.y1 = y;
+                                edge.from_output = Some(id);
+                            }
+                            Some(((x, y), Connector::Input(id))) => {
+                                edge.x2 = x;
+                                edge.y2 = y;
+                                edge.to_input = Some(id);
+                            }
+                            // snap back on an invalid drop
+                            None => *edge = original.clone(),
+                        }
+                        graph.insert_edge(edge);
+                    }
+                }
+                interaction_mode = InteractionMode::None; // reset interaction mode
+            }
+            WorkspaceAction::PanActivate(PanCmd { x, y }) => {
+                if interaction_mode == InteractionMode::None {
+                    interaction_mode = InteractionMode::Pan(x, y);
+                }
+            }
+            WorkspaceAction::Pan(PanCmd { x, y }) => {
+                if let InteractionMode::Pan(last_x, last_y) = interaction_mode {
+                    if let Some(ref mut viewport) = viewport {
+                        viewport.transform.x += x - last_x;
+                        viewport.transform.y += y - last_y;
+                    }
+                    interaction_mode = InteractionMode::Pan(x, y);
+                }
+            }
+            WorkspaceAction::PanDeactivate => {
+                if let InteractionMode::Pan(..) = interaction_mode {
+                    interaction_mode = InteractionMode::None;
+                }
+            }
+            WorkspaceAction::PinchActivate(pinch) => {
+                // cancel the interaction started by the first pointer
+                match interaction_mode {
+                    InteractionMode::NewEdgeDrag(_) => {
+                        // remove the temp edge
+                        if let Some(edge) = edges.pop() {
+                            graph.remove_edge(&edge.id);
+                        }
+                    }
+                    InteractionMode::EdgeUpdate(EdgeUpdateMode {
+                        ref edge_id,
+                        ref original,
+                        ..
+                    }) => {
+                        if let Some(edge) = edges.iter_mut().find(|e| e.id == *edge_id) {
+                            *edge = original.clone();
+                            graph.insert_edge(edge);
                         }
+                    }
+                    _ => {}
+                }
+                interaction_mode = InteractionMode::Pinch(pinch);
+            }
+            WorkspaceAction::Pinch(pinch) => {
+                if let InteractionMode::Pinch(ref last) = interaction_mode {
+                    if let Some(ref mut viewport) = viewport {
+                        let factor = if last.distance > 0. {
+                            pinch.distance / last.distance
+                        } else {
+                            1.
+                        };
+                        // zoom around the last center, then follow the center
+                        let ViewportTransform { x, y, zoom } =
+                            viewport
+                                .transform
+                                .zoom_at(factor, last.center_x, last.center_y);
+                        viewport.transform = ViewportTransform {
+                            x: x + pinch.center_x - last.center_x,
+                            y: y + pinch.center_y - last.center_y,
+                            zoom,
+                        };
+                    }
+                    interaction_mode = InteractionMode::Pinch(pinch);
+                }
+            }
+            WorkspaceAction::PinchDeactivate => {
+                if let InteractionMode::Pinch(_) = interaction_mode {
+                    interaction_mode = InteractionMode::None;
+                }
+            }
+            WorkspaceAction::Zoom(ZoomCmd { factor, x, y }) => {
+                if let Some(ref mut viewport) = viewport {
+                    viewport.transform = viewport.transform.zoom_at(factor, x, y);
+                }
+            }
+            WorkspaceAction::NodeSelect(NodeSelectCmd { id, additive }) => {
+                if additive {
+                    // toggle the node in the selection
+                    if let Some(index) = selection.iter().position(|s| *s == id) {
+                        selection.remove(index);
                     } else {
-                        // remove the temp edge if not connected
-                        edges.pop();
+                        selection.push(id);
                     }
                 } else {
-                    // remove the temp edge if not connected
-                    edges.pop();
+                    selection = vec![id];
                 }
-                interaction_mode = InteractionMode::None; // reset interaction mode
-                Self {
-                    viewport,
+            }
+            WorkspaceAction::SelectionClear => {
+                selection.clear();
+            }
+            WorkspaceAction::NodesMove(NodesMoveCmd { ids, dx, dy }) => {
+                for node in nodes.iter_mut().filter(|node| ids.contains(&node.id)) {
+                    node.x += dx;
+                    node.y += dy;
+                }
+                for comment in comments.iter_mut().filter(|c| ids.contains(&c.id)) {
+                    comment.x += dx;
+                    comment.y += dy;
+                }
+            }
+            WorkspaceAction::KeyboardConnect(connector) => match interaction_mode {
+                InteractionMode::KeyboardConnect(ref from_connector) => {
+                    let valid =
+                        validate_connection(&nodes, &edges, from_connector, &connector, None)
+                            .and_then(|()| match acyclic {
+                                true => {
+                                    validate_acyclic(&graph, &nodes, from_connector, &connector)
+                                }
+                                false => Ok(()),
+                            });
+                    match valid {
+                        Ok(()) => {
+                            let mut edge = Edge::default();
+                            for c in [from_connector, &connector] {
+                                match c {
+                                    Connector::Output(id) => edge.from_output = Some(id.clone()),
+                                    Connector::Input(id) => edge.to_input = Some(id.clone()),
+                                }
+                            }
+                            announcement = format!(
+                                "Connected {} to {}",
+                                connector_label(&nodes, from_connector),
+                                connector_label(&nodes, &connector),
+                            );
+                            graph.insert_edge(&edge);
+                            edges.push(edge);
+                            if let Some(ref viewport) = viewport {
+                                for node in nodes.iter() {
+                                    update_node_edge_positions(viewport, node, &mut edges);
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            announcement = format!("Cannot connect, {}", err);
+                            notice = Some(announcement.clone());
+                        }
+                    }
+                    interaction_mode = InteractionMode::None;
+                }
+                InteractionMode::None | InteractionMode::EdgeHover(_) => {
+                    announcement = format!(
+                        "Connecting from {}. Activate another connector, or press Escape to cancel.",
+                        connector_label(&nodes, &connector)
+                    );
+                    interaction_mode = InteractionMode::KeyboardConnect(connector);
+                }
+                _ => {}
+            },
+            WorkspaceAction::KeyboardConnectCancel => {
+                if let InteractionMode::KeyboardConnect(_) = interaction_mode {
+                    announcement = "Connection cancelled".to_string();
+                    interaction_mode = InteractionMode::None;
+                }
+            }
+            WorkspaceAction::RefreshEdgePositions => {
+                if let Some(ref viewport) = viewport {
+                    for node in nodes.iter() {
+                        update_node_edge_positions(viewport, node, &mut edges);
+                    }
+                }
+            }
+            WorkspaceAction::AcyclicChange(new_acyclic) => {
+                acyclic = new_acyclic;
+            }
+            WorkspaceAction::Notice(message) => {
+                announcement = message.clone();
+                notice = Some(message);
+            }
+            WorkspaceAction::NodeStatusChange(cmds) => {
+                for NodeStatusCmd {
+                    id,
+                    status,
+                    progress,
+                } in cmds
+                {
+                    if let Some(node) = nodes.iter_mut().find(|n| n.id == id) {
+                        node.status = status;
+                        node.progress = progress;
+                    }
+                }
+            }
+            WorkspaceAction::EdgeActivityChange(cmds) => {
+                for EdgeActivityCmd {
+                    id,
+                    animated,
+                    activity,
+                } in cmds
+                {
+                    if let Some(edge) = edges.iter_mut().find(|e| e.id == id) {
+                        edge.animated = animated;
+                        edge.activity = activity;
+                    }
+                }
+            }
+            WorkspaceAction::Paste(mut values) => {
+                // clipboard text may hold edges with loose or unknown ends
+                values.repair();
+                let pasted = regenerate_ids(values, PASTE_OFFSET, PASTE_OFFSET);
+                announcement = format!(
+                    "Pasted {} nodes and {} comments",
+                    pasted.nodes.len(),
+                    pasted.comments.len()
+                );
+                selection = pasted
+                    .nodes
+                    .iter()
+                    .map(|node| node.id.clone())
+                    .chain(pasted.comments.iter().map(|comment| comment.id.clone()))
+                    .collect();
+                for node in pasted.nodes.iter() {
+                    graph.insert_node(node);
+                }
+                for edge in pasted.edges.iter() {
+                    graph.insert_edge(edge);
+                }
+                nodes.extend(pasted.nodes);
+                edges.extend(pasted.edges);
+                comments.extend(pasted.comments);
+                interaction_mode = InteractionMode::None;
+            }
+            WorkspaceAction::DuplicateSelection => {
+                let copied = copy_subgraph(&nodes, &edges, &comments, &selection);
+                let duplicated = regenerate_ids(copied, PASTE_OFFSET, PASTE_OFFSET);
+                announcement = format!(
+                    "Duplicated {} nodes and {} comments",
+                    duplicated.nodes.len(),
+                    duplicated.comments.len()
+                );
+                selection = duplicated
+                    .nodes
+                    .iter()
+                    .map(|node| node.id.clone())
+                    .chain(duplicated.comments.iter().map(|comment| comment.id.clone()))
+                    .collect();
+                for node in duplicated.nodes.iter() {
+                    graph.insert_node(node);
+                }
+                for edge in duplicated.edges.iter() {
+                    graph.insert_edge(edge);
+                }
+                nodes.extend(duplicated.nodes);
+                edges.extend(duplicated.edges);
+                comments.extend(duplicated.comments);
+                interaction_mode = InteractionMode::None;
+            }
+            WorkspaceAction::AddNode(node) => {
+                update_group_membership(&mut groups, &node);
+                announcement = format!("Added {}", node.title);
+                selection = vec![node.id.clone()];
+                graph.insert_node(&node);
+                nodes.push(node);
+            }
+            WorkspaceAction::NodesDelete(ids) => {
+                let (deleted, kept): (Vec<Node>, Vec<Node>) =
+                    nodes.into_iter().partition(|node| ids.contains(&node.id));
+                let deleted_ports = deleted
+                    .iter()
+                    .flat_map(|node| node.port_ids())
+                    .collect::<Vec<_>>();
+                let deleted_edges = attached_edge_ids(&edges, &deleted_ports);
+                edges.retain(|edge| !deleted_edges.contains(&edge.id));
+                for id in deleted_edges.iter() {
+                    graph.remove_edge(id);
+                }
+                for node in deleted.iter() {
+                    graph.remove_node(&node.id);
+                }
+                let comment_count = comments.len();
+                comments.retain(|comment| !ids.contains(&comment.id));
+                announcement = format!(
+                    "Deleted {} nodes and {} comments",
+                    deleted.len(),
+                    comment_count - comments.len()
+                );
+                selection.retain(|id| !ids.contains(id));
+                for group in groups.iter_mut() {
+                    group.children.retain(|id| !ids.contains(id));
+                }
+                nodes = kept;
+                interaction_mode = InteractionMode::None;
+            }
+            WorkspaceAction::EdgesDelete(ids) => {
+                let count = edges.len();
+                edges.retain(|edge| !ids.contains(&edge.id));
+                for id in ids.iter() {
+                    graph.remove_edge(id);
+                }
+                announcement = format!("Deleted {} edges", count - edges.len());
+                if let InteractionMode::EdgeHover(ref id) = interaction_mode {
+                    if ids.contains(id) {
+                        interaction_mode = InteractionMode::None;
+                    }
+                }
+            }
+            WorkspaceAction::ContextMenuOpen(ContextMenuOpenCmd { target, x, y }) => {
+                if let Some(ref viewport) = viewport {
+                    context_menu = Some(ContextMenu {
+                        target,
+                        x: viewport.flow_x_from_abs(x),
+                        y: viewport.flow_y_from_abs(y),
+                    });
+                }
+            }
+            WorkspaceAction::ContextMenuClose => {
+                context_menu = None;
+            }
+            WorkspaceAction::QuickAddOpen(QuickAddOpenCmd { x, y }) => {
+                if let Some(ref viewport) = viewport {
+                    context_menu = None;
+                    quick_add = Some(QuickAdd {
+                        x: viewport.flow_x_from_abs(x),
+                        y: viewport.flow_y_from_abs(y),
+                        from_connector: None,
+                    });
+                }
+            }
+            WorkspaceAction::GroupDragActivate(GroupDragActivateCmd { id, x, y }) => {
+                if let Some(ref viewport) = viewport {
+                    interaction_mode = InteractionMode::GroupDrag(
+                        id,
+                        viewport.flow_x_from_abs(x),
+                        viewport.flow_y_from_abs(y),
+                    );
+                }
+            }
+            WorkspaceAction::GroupDrag(DragGroupCmd { x, y }) => {
+                if let (InteractionMode::GroupDrag(id, last_x, last_y), Some(viewport)) =
+                    (&interaction_mode, &viewport)
+                {
+                    let x = viewport.flow_x_from_abs(x);
+                    let y = viewport.flow_y_from_abs(y);
+                    let (dx, dy) = (x - last_x, y - last_y);
+                    if let Some(group) = groups.iter_mut().find(|g| g.id == *id) {
+                        group.x += dx;
+                        group.y += dy;
+                        for node in nodes
+                            .iter_mut()
+                            .filter(|node| group.children.contains(&node.id))
+                        {
+                            node.x += dx;
+                            node.y += dy;
+                        }
+                    }
+                    interaction_mode = InteractionMode::GroupDrag(id.clone(), x, y);
+                }
+            }
+            WorkspaceAction::GroupDragDeactivate => {
+                if let InteractionMode::GroupDrag(..) = interaction_mode {
+                    interaction_mode = InteractionMode::None;
+                }
+            }
+            WorkspaceAction::GroupToggleCollapse(id) => {
+                if let Some(group) = groups.iter_mut().find(|g| g.id == id) {
+                    group.collapsed = !group.collapsed;
+                    announcement = format!(
+                        "{} {}",
+                        if group.collapsed {
+                            "Collapsed"
+                        } else {
+                            "Expanded"
+                        },
+                        group.title
+                    );
+                }
+            }
+            WorkspaceAction::GroupSelection => {
+                let children = nodes
+                    .iter()
+                    .filter(|node| selection.contains(&node.id))
+                    .collect::<Vec<_>>();
+                if !children.is_empty() {
+                    // bounds of the selected nodes
+                    let min_x = children
+                        .iter()
+                        .map(|n| n.x)
+                        .fold(StandardUnit::INFINITY, StandardUnit::min);
+                    let min_y = children
+                        .iter()
+                        .map(|n| n.y)
+                        .fold(StandardUnit::INFINITY, StandardUnit::min);
+                    let max_x = children
+                        .iter()
+                        .map(|n| n.x + NODE_WIDTH)
+                        .fold(StandardUnit::NEG_INFINITY, StandardUnit::max);
+                    let max_y = children
+                        .iter()
+                        .map(|n| n.y + NODE_HEIGHT)
+                        .fold(StandardUnit::NEG_INFINITY, StandardUnit::max);
+                    let children = children.iter().map(|n| n.id.clone()).collect::<Vec<_>>();
+                    // a node belongs to one group only
+                    for group in groups.iter_mut() {
+                        group.children.retain(|id| !children.contains(id));
+                    }
+                    let group = Group {
+                        id: StandardId::generate(),
+                        title: format!("Group {}", groups.len() + 1),
+                        color: "#808080".to_string(),
+                        x: min_x - GROUP_PADDING,
+                        y: min_y - GROUP_PADDING - GROUP_HEADER_HEIGHT,
+                        width: max_x - min_x + 2. * GROUP_PADDING,
+                        height: max_y - min_y + 2. * GROUP_PADDING + GROUP_HEADER_HEIGHT,
+                        collapsed: false,
+                        children,
+                    };
+                    announcement = format!("Created {}", group.title);
+                    groups.push(group);
+                }
+            }
+            WorkspaceAction::AddComment(comment) => {
+                announcement = "Added comment".to_string();
+                selection = vec![comment.id.clone()];
+                comments.push(comment);
+            }
+            WorkspaceAction::CommentUpdate(comment) => {
+                if let Some(c) = comments.iter_mut().find(|c| c.id == comment.id) {
+                    *c = comment;
+                }
+            }
+            WorkspaceAction::CommentDragActivate(CommentDragActivateCmd { id, x, y, resize }) => {
+                if let Some(ref viewport) = viewport {
+                    let x = viewport.flow_x_from_abs(x);
+                    let y = viewport.flow_y_from_abs(y);
+                    interaction_mode = if resize {
+                        InteractionMode::CommentResize(id, x, y)
+                    } else {
+                        InteractionMode::CommentDrag(id, x, y)
+                    };
+                }
+            }
+            WorkspaceAction::CommentDrag(DragCommentCmd { x, y }) => {
+                if let Some(ref viewport) = viewport {
+                    let x = viewport.flow_x_from_abs(x);
+                    let y = viewport.flow_y_from_abs(y);
+                    match interaction_mode {
+                        InteractionMode::CommentDrag(ref id, last_x, last_y) => {
+                            if let Some(comment) = comments.iter_mut().find(|c| c.id == *id) {
+                                comment.x += x - last_x;
+                                comment.y += y - last_y;
+                            }
+                            interaction_mode = InteractionMode::CommentDrag(id.clone(), x, y);
+                        }
+                        InteractionMode::CommentResize(ref id, last_x, last_y) => {
+                            if let Some(comment) = comments.iter_mut().find(|c| c.id == *id) {
+                                comment.width = (comment.width + x - last_x).max(COMMENT_MIN_SIZE);
+                                comment.height =
+                                    (comment.height + y - last_y).max(COMMENT_MIN_SIZE);
+                            }
+                            interaction_mode = InteractionMode::CommentResize(id.clone(), x, y);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            WorkspaceAction::CommentDragDeactivate => {
+                if let InteractionMode::CommentDrag(..) | InteractionMode::CommentResize(..) =
+                    interaction_mode
+                {
+                    interaction_mode = InteractionMode::None;
+                }
+            }
+            WorkspaceAction::NodeFieldChange(NodeFieldChangeCmd { id, key, value }) => {
+                if let Some(node) = nodes.iter_mut().find(|n| n.id == id) {
+                    node.values.insert(key, value);
+                }
+            }
+            WorkspaceAction::InputValueChange(InputValueChangeCmd { id, value }) => {
+                if let Some(input) = nodes
+                    .iter_mut()
+                    .flat_map(|node| node.inputs.iter_mut())
+                    .find(|input| input.id == id)
+                {
+                    input.value = Some(value);
+                }
+            }
+            WorkspaceAction::NodeToggleCollapse(id) => {
+                if let Some(node) = nodes.iter_mut().find(|n| n.id == id) {
+                    node.collapsed = !node.collapsed;
+                    announcement = format!(
+                        "{} {}",
+                        if node.collapsed {
+                            "Collapsed"
+                        } else {
+                            "Expanded"
+                        },
+                        node.title
+                    );
+                }
+            }
+            WorkspaceAction::SubflowCreate => {
+                let mut values = YewFlowValues {
                     nodes,
                     edges,
-                    interaction_mode,
+                    groups,
+                    comments,
+                };
+                let title = format!("Subflow ({} nodes)", selection.len());
+                if let Some(id) = collapse_to_subflow(&mut values, &selection, title) {
+                    announcement = format!("Collapsed {} nodes into a subflow", selection.len());
+                    selection = vec![id];
+                }
+                nodes = values.nodes;
+                edges = values.edges;
+                groups = values.groups;
+                comments = values.comments;
+                graph = GraphIndex::new(&nodes, &edges);
+                interaction_mode = InteractionMode::None;
+            }
+            WorkspaceAction::SubflowEnter(id) => {
+                let subflow = nodes.iter().find(|node| node.id == id).and_then(|node| {
+                    node.subflow
+                        .clone()
+                        .map(|inner| (node.title.clone(), inner))
+                });
+                if let Some((title, inner)) = subflow {
+                    announcement = format!("Opened {}", title);
+                    subflow_path.push(SubflowLevel {
+                        node_id: id,
+                        title,
+                        values: YewFlowValues {
+                            nodes,
+                            edges,
+                            groups,
+                            comments,
+                        },
+                    });
+                    nodes = inner.nodes;
+                    edges = inner.edges;
+                    groups = inner.groups;
+                    comments = inner.comments;
+                    graph = GraphIndex::new(&nodes, &edges);
+                    selection.clear();
+                    interaction_mode = InteractionMode::None;
+                    context_menu = None;
+                    quick_add = None;
+                }
+            }
+            WorkspaceAction::SubflowExit(depth) => {
+                if depth < subflow_path.len() {
+                    let mut values = YewFlowValues {
+                        nodes,
+                        edges,
+                        groups,
+                        comments,
+                    };
+                    while subflow_path.len() > depth {
+                        if let Some(level) = subflow_path.pop() {
+                            values = close_subflow_level(level, values);
+                        }
+                    }
+                    nodes = values.nodes;
+                    edges = values.edges;
+                    groups = values.groups;
+                    comments = values.comments;
+                    graph = GraphIndex::new(&nodes, &edges);
+                    announcement = "Went back to the parent flow".to_string();
+                    selection.clear();
+                    interaction_mode = InteractionMode::None;
+                    context_menu = None;
+                    quick_add = None;
+                }
+            }
+            WorkspaceAction::QuickAddClose => {
+                quick_add = None;
+            }
+            WorkspaceAction::QuickAddSelect(template) => {
+                if let Some(QuickAdd {
+                    x,
+                    y,
+                    from_connector,
+                }) = quick_add.take()
+                {
+                    // center the node on the popup position
+                    let node = template.instantiate(x - NODE_WIDTH / 2., y - NODE_HEIGHT / 2.);
+                    update_group_membership(&mut groups, &node);
+                    announcement = format!("Added {}", node.title);
+                    selection = vec![node.id.clone()];
+                    graph.insert_node(&node);
+                    nodes.push(node.clone());
+                    // the connector may have been deleted while the popup was open
+                    let from_connector = from_connector.filter(|connector| match connector {
+                        Connector::Input(id) | Connector::Output(id) => {
+                            graph.node_of_port(id).is_some()
+                        }
+                    });
+                    if let Some(from_connector) = from_connector {
+                        // connect the first compatible connector of the new node
+                        let candidates = node
+                            .inputs
+                            .iter()
+                            .map(|input| Connector::Input(input.id.clone()))
+                            .chain(
+                                node.outputs
+                                    .iter()
+                                    .map(|output| Connector::Output(output.id.clone())),
+                            );
+                        for connector in candidates {
+                            if validate_connection(
+                                &nodes,
+                                &edges,
+                                &from_connector,
+                                &connector,
+                                None,
+                            )
+                            .is_ok()
+                            {
+                                let mut edge = Edge::default();
+                                for c in [&from_connector, &connector] {
+                                    match c {
+                                        Connector::Output(id) => {
+                                            edge.from_output = Some(id.clone())
+                                        }
+                                        Connector::Input(id) => edge.to_input = Some(id.clone()),
+                                    }
+                                }
+                                announcement = format!(
+                                    "Added {} and connected {} to {}",
+                                    node.title,
+                                    connector_label(&nodes, &from_connector),
+                                    connector_label(&nodes, &connector),
+                                );
+                                graph.insert_edge(&edge);
+                                edges.push(edge);
+                                break;
+                            }
+                        }
+                    }
                 }
-                .into()
             }
         }
+        Self {
+            viewport,
+            nodes,
+            edges,
+            interaction_mode,
+            selection,
+            announcement,
+            context_menu,
+            quick_add,
+            groups,
+            subflow_path,
+            comments,
+            acyclic,
+            notice,
+            graph,
+            hovered_node,
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str) -> Node {
+        NodeTemplate {
+            kind: None,
+            title: id.to_string(),
+            color: "#000000".to_string(),
+            inputs: 1,
+            outputs: 1,
+            fields: Vec::new(),
+        }
+        .instantiate(0., 0.)
+    }
+
+    /// Reduce `action` and check that the index matches a fresh build.
+    fn reduce(store: Rc<WorkspaceStore>, action: WorkspaceAction) -> Rc<WorkspaceStore> {
+        let store = store.reduce(action);
+        assert_eq!(store.graph, GraphIndex::new(&store.nodes, &store.edges));
+        store
+    }
+
+    #[test]
+    fn graph_index_follows_edits() {
+        let (a, b, c, d) = (node("a"), node("b"), node("c"), node("d"));
+        let edge = Edge {
+            id: StandardId::generate(),
+            from_output: Some(a.outputs[0].id.clone()),
+            to_input: Some(b.inputs[0].id.clone()),
+            ..Default::default()
+        };
+        let values = YewFlowValues {
+            nodes: vec![a.clone(), b.clone(), c.clone()],
+            edges: vec![edge.clone()],
+            groups: Vec::new(),
+            comments: Vec::new(),
+        };
+        let mut store = Rc::new(WorkspaceStore::default());
+        store = reduce(store, WorkspaceAction::Init(Some(values)));
+        store = reduce(store, WorkspaceAction::AddNode(d.clone()));
+        for connector in [
+            Connector::Output(c.outputs[0].id.clone()),
+            Connector::Input(d.inputs[0].id.clone()),
+        ] {
+            store = reduce(store, WorkspaceAction::KeyboardConnect(connector));
+        }
+        assert_eq!(store.edges.len(), 2);
+
+        // detach an end, then snap back on an invalid drop
+        store = reduce(
+            store,
+            WorkspaceAction::EdgeUpdateActivate(EdgeUpdateActivateCmd {
+                edge_id: edge.id.clone(),
+                end: EdgeEnd::To,
+            }),
+        );
+        assert_eq!(store.graph.edge_nodes(&edge.id), None);
+        store = reduce(
+            store,
+            WorkspaceAction::EdgeUpdateDeactivate(NewEdgeDragDeactivateCmd {
+                to_reference: None,
+                to_connector: None,
+                open_quick_add: false,
+            }),
+        );
+        assert_eq!(store.graph.edge_nodes(&edge.id), Some((&a.id, &b.id)));
+
+        // a pinch cancels edge drags
+        let pinch = || {
+            WorkspaceAction::PinchActivate(PinchCmd {
+                distance: 100.,
+                center_x: 0.,
+                center_y: 0.,
+            })
+        };
+        store = reduce(
+            store,
+            WorkspaceAction::EdgeUpdateActivate(EdgeUpdateActivateCmd {
+                edge_id: edge.id.clone(),
+                end: EdgeEnd::From,
+            }),
+        );
+        store = reduce(store, pinch());
+        store = reduce(store, WorkspaceAction::PinchDeactivate);
+        assert_eq!(store.graph.edge_nodes(&edge.id), Some((&a.id, &b.id)));
+        // the temp edge of a new edge drag is only added with a rendered connector
+        let from_connector = Connector::Output(c.outputs[0].id.clone());
+        let mut edges = store.edges.clone();
+        edges.push(Edge {
+            from_output: Some(c.outputs[0].id.clone()),
+            ..Default::default()
+        });
+        store = Rc::new(WorkspaceStore {
+            graph: GraphIndex::new(&store.nodes, &edges),
+            edges,
+            interaction_mode: InteractionMode::NewEdgeDrag(NewEdgeDragMode {
+                from_connector,
+                snap_target: None,
+            }),
+            ..(*store).clone()
+        });
+        store = reduce(store, pinch());
+        store = reduce(store, WorkspaceAction::PinchDeactivate);
+        assert_eq!(store.edges.len(), 2);
+
+        for (id, additive) in [(&a.id, false), (&b.id, true)] {
+            store = reduce(
+                store,
+                WorkspaceAction::NodeSelect(NodeSelectCmd {
+                    id: id.clone(),
+                    additive,
+                }),
+            );
+        }
+        store = reduce(store, WorkspaceAction::DuplicateSelection);
+        let copied = copy_subgraph(&store.nodes, &store.edges, &[], std::slice::from_ref(&a.id));
+        store = reduce(store, WorkspaceAction::Paste(copied));
+        assert_eq!(store.nodes.len(), 7);
+
+        store = Rc::new(WorkspaceStore {
+            quick_add: Some(QuickAdd {
+                x: 0.,
+                y: 0.,
+                from_connector: Some(Connector::Output(d.outputs[0].id.clone())),
+            }),
+            ..(*store).clone()
+        });
+        let edge_count = store.edges.len();
+        store = reduce(
+            store,
+            WorkspaceAction::QuickAddSelect(NodeTemplate {
+                kind: None,
+                title: "added".to_string(),
+                color: "#000000".to_string(),
+                inputs: 1,
+                outputs: 1,
+                fields: Vec::new(),
+            }),
+        );
+        assert_eq!(store.edges.len(), edge_count + 1);
+
+        store = reduce(store, WorkspaceAction::EdgesDelete(vec![edge.id.clone()]));
+        store = reduce(store, WorkspaceAction::NodesDelete(vec![c.id.clone()]));
+        store = reduce(store, WorkspaceAction::SubflowCreate);
+        let subflow_id = store.selection[0].clone();
+        store = reduce(store, WorkspaceAction::SubflowEnter(subflow_id));
+        assert_eq!(store.subflow_path.len(), 1);
+        store = reduce(store, WorkspaceAction::SubflowExit(0));
+        assert!(store.subflow_path.is_empty());
+    }
+
+    #[test]
+    fn paste_drops_edges_with_loose_or_unknown_ends() {
+        let (a, b) = (node("a"), node("b"));
+        let edge = |from_output: Option<&StandardId>, to_input: Option<&StandardId>| Edge {
+            id: StandardId::generate(),
+            from_output: from_output.cloned(),
+            to_input: to_input.cloned(),
+            ..Default::default()
+        };
+        let unknown = "unknown".to_string();
+        let values = YewFlowValues {
+            edges: vec![
+                edge(Some(&a.outputs[0].id), Some(&b.inputs[0].id)),
+                edge(Some(&a.outputs[0].id), None),
+                edge(Some(&unknown), Some(&b.inputs[0].id)),
+            ],
+            nodes: vec![a, b],
+            groups: Vec::new(),
+            comments: Vec::new(),
+        };
+        let store = reduce(
+            Rc::new(WorkspaceStore::default()),
+            WorkspaceAction::Paste(values),
+        );
+        assert_eq!(store.nodes.len(), 2);
+        assert_eq!(store.edges.len(), 1);
+        assert!(store.edges[0].from_output.is_some() && store.edges[0].to_input.is_some());
+    }
+
+    #[test]
+    fn quick_add_skips_deleted_connectors() {
+        let a = node("a");
+        let mut store = reduce(
+            Rc::new(WorkspaceStore::default()),
+            WorkspaceAction::AddNode(a.clone()),
+        );
+        store = Rc::new(WorkspaceStore {
+            quick_add: Some(QuickAdd {
+                x: 0.,
+                y: 0.,
+                from_connector: Some(Connector::Output(a.outputs[0].id.clone())),
+            }),
+            ..(*store).clone()
+        });
+        store = reduce(store, WorkspaceAction::NodesDelete(vec![a.id]));
+        store = reduce(
+            store,
+            WorkspaceAction::QuickAddSelect(NodeTemplate {
+                kind: None,
+                title: "added".to_string(),
+                color: "#000000".to_string(),
+                inputs: 1,
+                outputs: 1,
+                fields: Vec::new(),
+            }),
+        );
+        assert_eq!(store.nodes.len(), 1);
+        assert!(store.edges.is_empty());
     }
 }
@@ -0,0 +1,294 @@
+use crate::{
+    components::node::models::{Node, NodeInput, NodeOutput},
+    store::attached_edge_ids,
+    types::{
+        standard_id::{IdentifierExt, StandardId},
+        standard_unit::StandardUnit,
+    },
+    workspace::YewFlowValues,
+};
+
+/// # Subflow Level
+///
+/// Parent level of the subflow currently open in the workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubflowLevel {
+    /// Id of the subflow node in the parent level.
+    pub node_id: StandardId,
+    /// Title of the subflow node, shown in the breadcrumb.
+    pub title: String,
+    /// Parent level values. The subflow node's inner graph is
+    /// outdated while the level is open.
+    pub values: YewFlowValues,
+}
+
+/// # Collapse To Subflow
+///
+/// Replace the nodes with `node_ids` by a single subflow node containing them
+/// and the edges between them. Edges crossing the selection boundary stay in
+/// `values` and get connected to the subflow node, which exposes one connector
+/// with a new id per inner connector they are attached to. Returns the id of
+/// the new node.
+pub fn collapse_to_subflow(
+    values: &mut YewFlowValues,
+    node_ids: &[StandardId],
+    title: String,
+) -> Option<StandardId> {
+    let (inner_nodes, outer_nodes): (Vec<Node>, Vec<Node>) = values
+        .nodes
+        .drain(..)
+        .partition(|node| node_ids.contains(&node.id));
+    values.nodes = outer_nodes;
+    if inner_nodes.is_empty() {
+        return None;
+    }
+    let inner_ports = inner_nodes
+        .iter()
+        .flat_map(|node| node.port_ids())
+        .collect::<Vec<_>>();
+    let is_inner = |id: &Option<StandardId>| {
+        id.as_ref()
+            .map(|id| inner_ports.contains(id))
+            .unwrap_or_default()
+    };
+
+    // (inner connector id, exposed connector id)
+    let mut inputs = Vec::<(StandardId, StandardId)>::new();
+    let mut outputs = Vec::<(StandardId, StandardId)>::new();
+    let expose = |ports: &mut Vec<(StandardId, StandardId)>, inner_id: StandardId| match ports
+        .iter()
+        .find(|(id, _)| *id == inner_id)
+    {
+        Some((_, exposed_id)) => exposed_id.clone(),
+        None => {
+            let exposed_id = StandardId::generate();
+            ports.push((inner_id, exposed_id.clone()));
+            exposed_id
+        }
+    };
+    let mut inner_edges = Vec::new();
+    let mut outer_edges = Vec::new();
+    for mut edge in values.edges.drain(..) {
+        match (is_inner(&edge.from_output), is_inner(&edge.to_input)) {
+            (true, true) => inner_edges.push(edge),
+            // crossing into the selection
+            (false, true) => {
+                let inner_id = edge.to_input.take().unwrap_or_default();
+                edge.to_input = Some(expose(&mut inputs, inner_id));
+                outer_edges.push(edge);
+            }
+            // crossing out of the selection
+            (true, false) => {
+                let inner_id = edge.from_output.take().unwrap_or_default();
+                edge.from_output = Some(expose(&mut outputs, inner_id));
+                outer_edges.push(edge);
+            }
+            (false, false) => outer_edges.push(edge),
+        }
+    }
+    values.edges = outer_edges;
+
+    // the nodes leave their groups
+    for group in values.groups.iter_mut() {
+        group.children.retain(|id| !node_ids.contains(id));
+    }
+
+    let x = inner_nodes
+        .iter()
+        .map(|node| node.x)
+        .fold(StandardUnit::INFINITY, StandardUnit::min);
+    let y = inner_nodes
+        .iter()
+        .map(|node| node.y)
+        .fold(StandardUnit::INFINITY, StandardUnit::min);
+    let node = Node {
+        id: StandardId::generate(),
+        kind: None,
+        title,
+        x,
+        y,
+        color: "#00aaaa".to_string(),
+        // exposed connectors keep the labels of the inner connectors
+        inputs: inputs
+            .into_iter()
+            .map(|(inner_id, id)| NodeInput {
+                label: inner_nodes
+                    .iter()
+                    .flat_map(|node| node.inputs.iter())
+                    .find(|input| input.id == inner_id)
+                    .and_then(|input| input.label.clone()),
+                exposes: Some(inner_id),
+                ..NodeInput::new(id)
+            })
+            .collect(),
+        outputs: outputs
+            .into_iter()
+            .map(|(inner_id, id)| NodeOutput {
+                label: inner_nodes
+                    .iter()
+                    .flat_map(|node| node.outputs.iter())
+                    .find(|output| output.id == inner_id)
+                    .and_then(|output| output.label.clone()),
+                exposes: Some(inner_id),
+                ..NodeOutput::new(id)
+            })
+            .collect(),
+        subflow: Some(Box::new(YewFlowValues {
+            nodes: inner_nodes,
+            edges: inner_edges,
+            groups: Vec::new(),
+            comments: Vec::new(),
+        })),
+        collapsed: false,
+        fields: Vec::new(),
+        values: Default::default(),
+        status: Default::default(),
+        progress: None,
+    };
+    let id = node.id.clone();
+    values.nodes.push(node);
+    Some(id)
+}
+
+/// # Close Subflow Level
+///
+/// Store the edited `inner` values in the subflow node of `level` and return
+/// the parent level. Exposed connectors whose inner connector was removed are
+/// dropped together with their edges.
+pub fn close_subflow_level(level: SubflowLevel, inner: YewFlowValues) -> YewFlowValues {
+    let SubflowLevel {
+        node_id,
+        mut values,
+        ..
+    } = level;
+    let inner_ports = inner
+        .nodes
+        .iter()
+        .flat_map(|node| node.port_ids())
+        .collect::<Vec<_>>();
+    let mut removed_ports = Vec::new();
+    if let Some(node) = values.nodes.iter_mut().find(|node| node.id == node_id) {
+        node.inputs.retain(|input| {
+            let keep = inner_ports.contains(input.exposed_id());
+            if !keep {
+                removed_ports.push(input.id.clone());
+            }
+            keep
+        });
+        node.outputs.retain(|output| {
+            let keep = inner_ports.contains(output.exposed_id());
+            if !keep {
+                removed_ports.push(output.id.clone());
+            }
+            keep
+        });
+        node.subflow = Some(Box::new(inner));
+    }
+    let removed_edges = attached_edge_ids(&values.edges, &removed_ports);
+    values
+        .edges
+        .retain(|edge| !removed_edges.contains(&edge.id));
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::edge::models::Edge;
+
+    fn node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            kind: None,
+            title: id.to_string(),
+            x: 0.,
+            y: 0.,
+            color: "#000000".to_string(),
+            inputs: vec![NodeInput::new(format!("{}-in", id))],
+            outputs: vec![NodeOutput::new(format!("{}-out", id))],
+            subflow: None,
+            collapsed: false,
+            fields: Vec::new(),
+            values: Default::default(),
+            status: Default::default(),
+            progress: None,
+        }
+    }
+
+    fn edge(from: &str, to: &str) -> Edge {
+        Edge {
+            from_output: Some(format!("{}-out", from)),
+            to_input: Some(format!("{}-in", to)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exposed_connectors_get_new_ids() {
+        let mut values = YewFlowValues {
+            nodes: vec![node("a"), node("b"), node("c")],
+            edges: vec![edge("a", "b"), edge("b", "c")],
+            groups: Vec::new(),
+            comments: Vec::new(),
+        };
+        let ids = ["a".to_string(), "b".to_string()];
+        let id = collapse_to_subflow(&mut values, &ids, "sub".to_string()).unwrap();
+
+        let subflow = values.nodes.iter().find(|node| node.id == id).unwrap();
+        assert!(subflow.inputs.is_empty());
+        let output = &subflow.outputs[0];
+        assert_eq!(output.exposes.as_deref(), Some("b-out"));
+        assert_ne!(output.id, "b-out");
+        // the crossing edge moves to the exposed connector
+        let crossing = values
+            .edges
+            .iter()
+            .find(|e| e.to_input.as_deref() == Some("c-in"));
+        assert_eq!(crossing.unwrap().from_output.as_ref(), Some(&output.id));
+        let inner = subflow.subflow.as_ref().unwrap();
+        assert!(inner
+            .nodes
+            .iter()
+            .all(|node| !node.port_ids().contains(&output.id)));
+    }
+
+    #[test]
+    fn closing_drops_connectors_of_removed_inner_ports() {
+        let mut values = YewFlowValues {
+            nodes: vec![node("a"), node("b")],
+            edges: vec![edge("a", "b")],
+            groups: Vec::new(),
+            comments: Vec::new(),
+        };
+        let id = collapse_to_subflow(&mut values, &["a".to_string()], "sub".to_string()).unwrap();
+        let level = SubflowLevel {
+            node_id: id.clone(),
+            title: "sub".to_string(),
+            values: values.clone(),
+        };
+
+        let unchanged = close_subflow_level(
+            level.clone(),
+            YewFlowValues {
+                nodes: vec![node("a")],
+                edges: Vec::new(),
+                groups: Vec::new(),
+                comments: Vec::new(),
+            },
+        );
+        assert_eq!(unchanged.edges.len(), 1);
+
+        let emptied = close_subflow_level(
+            level,
+            YewFlowValues {
+                nodes: Vec::new(),
+                edges: Vec::new(),
+                groups: Vec::new(),
+                comments: Vec::new(),
+            },
+        );
+        let subflow = emptied.nodes.iter().find(|node| node.id == id).unwrap();
+        assert!(subflow.outputs.is_empty());
+        assert!(emptied.edges.is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+use web_sys::Element;
+use yew::prelude::*;
+
+/// Capture the pointer on the event target so that the interaction
+/// continues even when the pointer leaves the element/workspace.
+pub(crate) fn capture_pointer(e: &PointerEvent) {
+    if let Some(target) = e.target_dyn_into::<Element>() {
+        capture_pointer_on(&target, e);
+    }
+}
+
+/// Capture the pointer of `e` on `element` instead of the event target.
+pub(crate) fn capture_pointer_on(element: &Element, e: &PointerEvent) {
+    let _ = element.set_pointer_capture(e.pointer_id());
+}
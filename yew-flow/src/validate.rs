@@ -0,0 +1,528 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::node::models::Node,
+    constants::NODE_WIDTH,
+    types::standard_id::{IdentifierExt, StandardId},
+    workspace::YewFlowValues,
+};
+
+/// Kind of item a duplicated id belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Node,
+    /// Input or output of a node.
+    Port,
+    Edge,
+    Group,
+    Comment,
+}
+
+impl Display for ItemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemKind::Node => write!(f, "node"),
+            ItemKind::Port => write!(f, "connector"),
+            ItemKind::Edge => write!(f, "edge"),
+            ItemKind::Group => write!(f, "group"),
+            ItemKind::Comment => write!(f, "comment"),
+        }
+    }
+}
+
+/// # Diagnostic
+///
+/// Problem found by [`YewFlowValues::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diagnostic {
+    /// Several items of `kind` share `id`.
+    DuplicateId { kind: ItemKind, id: StandardId },
+    /// `from_output` of the edge is not an output of any node.
+    DanglingOutput {
+        edge_id: StandardId,
+        port_id: StandardId,
+    },
+    /// `to_input` of the edge is not an input of any node.
+    DanglingInput {
+        edge_id: StandardId,
+        port_id: StandardId,
+    },
+    /// The edge is missing one or both of its ends.
+    HalfConnectedEdge { edge_id: StandardId },
+    /// The group lists a node that does not exist.
+    DanglingGroupChild {
+        group_id: StandardId,
+        node_id: StandardId,
+    },
+    /// The nodes are drawn on top of each other.
+    OverlappingNodes { a: StandardId, b: StandardId },
+}
+
+impl Diagnostic {
+    /// Whether the values are broken, as opposed to merely looking odd.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Diagnostic::OverlappingNodes { .. })
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diagnostic::DuplicateId { kind, id } => {
+                write!(f, "{} id \"{}\" is used twice", kind, id)
+            }
+            Diagnostic::DanglingOutput { edge_id, port_id } => write!(
+                f,
+                "edge \"{}\" starts at unknown output \"{}\"",
+                edge_id, port_id
+            ),
+            Diagnostic::DanglingInput { edge_id, port_id } => write!(
+                f,
+                "edge \"{}\" ends at unknown input \"{}\"",
+                edge_id, port_id
+            ),
+            Diagnostic::HalfConnectedEdge { edge_id } => {
+                write!(f, "edge \"{}\" is missing an end", edge_id)
+            }
+            Diagnostic::DanglingGroupChild { group_id, node_id } => write!(
+                f,
+                "group \"{}\" contains unknown node \"{}\"",
+                group_id, node_id
+            ),
+            Diagnostic::OverlappingNodes { a, b } => {
+                write!(f, "nodes \"{}\" and \"{}\" overlap", a, b)
+            }
+        }
+    }
+}
+
+/// # Integrity Policy
+///
+/// What the `Workspace` does with values that fail [`YewFlowValues::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityPolicy {
+    /// Load the values as they are, without validating them.
+    Ignore,
+    /// Load the values after [`YewFlowValues::repair`].
+    Repair,
+    /// Keep the current flow when the values have errors.
+    Refuse,
+}
+
+impl Default for IntegrityPolicy {
+    fn default() -> Self {
+        IntegrityPolicy::Ignore
+    }
+}
+
+/// Ids occurring more than once, in order of their first occurrence.
+fn duplicates<'a>(ids: impl Iterator<Item = &'a StandardId>) -> Vec<&'a StandardId> {
+    let mut counts = HashMap::new();
+    let mut order = Vec::new();
+    for id in ids {
+        let count = counts.entry(id).or_insert(0);
+        *count += 1;
+        if *count == 2 {
+            order.push(id);
+        }
+    }
+    order
+}
+
+/// Whether the bodies of `a` and `b` intersect. Touching edges don't count.
+fn overlap(a: &Node, b: &Node) -> bool {
+    a.x < b.x + NODE_WIDTH
+        && b.x < a.x + NODE_WIDTH
+        && a.y < b.y + b.height()
+        && b.y < a.y + a.height()
+}
+
+impl YewFlowValues {
+    /// Check that the edges and groups refer to existing nodes and connectors,
+    /// that ids are unique and that nodes don't overlap. Nodes inside subflows
+    /// are not checked, validate `Node::subflow` for those.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let ports = self.nodes.iter().flat_map(|node| {
+            let inputs = node.inputs.iter().map(|input| &input.id);
+            inputs.chain(node.outputs.iter().map(|output| &output.id))
+        });
+        let ids = [
+            (
+                ItemKind::Node,
+                duplicates(self.nodes.iter().map(|node| &node.id)),
+            ),
+            (ItemKind::Port, duplicates(ports)),
+            (
+                ItemKind::Edge,
+                duplicates(self.edges.iter().map(|edge| &edge.id)),
+            ),
+            (
+                ItemKind::Group,
+                duplicates(self.groups.iter().map(|group| &group.id)),
+            ),
+            (
+                ItemKind::Comment,
+                duplicates(self.comments.iter().map(|comment| &comment.id)),
+            ),
+        ];
+        for (kind, ids) in ids {
+            diagnostics.extend(ids.into_iter().map(|id| Diagnostic::DuplicateId {
+                kind,
+                id: id.clone(),
+            }));
+        }
+
+        let outputs = self
+            .nodes
+            .iter()
+            .flat_map(|node| node.outputs.iter().map(|output| &output.id))
+            .collect::<HashSet<_>>();
+        let inputs = self
+            .nodes
+            .iter()
+            .flat_map(|node| node.inputs.iter().map(|input| &input.id))
+            .collect::<HashSet<_>>();
+        for edge in self.edges.iter() {
+            let (from_output, to_input) = match (&edge.from_output, &edge.to_input) {
+                (Some(from_output), Some(to_input)) => (from_output, to_input),
+                _ => {
+                    diagnostics.push(Diagnostic::HalfConnectedEdge {
+                        edge_id: edge.id.clone(),
+                    });
+                    continue;
+                }
+            };
+            if !outputs.contains(from_output) {
+                diagnostics.push(Diagnostic::DanglingOutput {
+                    edge_id: edge.id.clone(),
+                    port_id: from_output.clone(),
+                });
+            }
+            if !inputs.contains(to_input) {
+                diagnostics.push(Diagnostic::DanglingInput {
+                    edge_id: edge.id.clone(),
+                    port_id: to_input.clone(),
+                });
+            }
+        }
+
+        let node_ids = self
+            .nodes
+            .iter()
+            .map(|node| &node.id)
+            .collect::<HashSet<_>>();
+        for group in self.groups.iter() {
+            for child in group.children.iter() {
+                if !node_ids.contains(child) {
+                    diagnostics.push(Diagnostic::DanglingGroupChild {
+                        group_id: group.id.clone(),
+                        node_id: child.clone(),
+                    });
+                }
+            }
+        }
+
+        for (i, a) in self.nodes.iter().enumerate() {
+            for b in self.nodes.iter().skip(i + 1) {
+                if overlap(a, b) {
+                    diagnostics.push(Diagnostic::OverlappingNodes {
+                        a: a.id.clone(),
+                        b: b.id.clone(),
+                    });
+                }
+            }
+        }
+        diagnostics
+    }
+
+    /// Fix the errors found by [`YewFlowValues::validate`] and return them.
+    ///
+    /// - Of items sharing an id the first one is kept.
+    /// - Connectors sharing an id get new ids, except for the first one.
+    /// - Edges with a missing or unknown end are removed.
+    /// - Unknown group children are removed.
+    ///
+    /// Overlapping nodes are left as they are.
+    pub fn repair(&mut self) -> Vec<Diagnostic> {
+        let diagnostics = self.validate();
+        if !diagnostics.iter().any(Diagnostic::is_error) {
+            return diagnostics;
+        }
+
+        let mut seen = HashSet::new();
+        self.nodes.retain(|node| seen.insert(node.id.clone()));
+        let mut seen = HashSet::new();
+        self.edges.retain(|edge| seen.insert(edge.id.clone()));
+        let mut seen = HashSet::new();
+        self.groups.retain(|group| seen.insert(group.id.clone()));
+        let mut seen = HashSet::new();
+        self.comments
+            .retain(|comment| seen.insert(comment.id.clone()));
+
+        let mut seen = HashSet::new();
+        for node in self.nodes.iter_mut() {
+            for input in node.inputs.iter_mut() {
+                if !seen.insert(input.id.clone()) {
+                    input.id = StandardId::generate();
+                }
+            }
+            for output in node.outputs.iter_mut() {
+                if !seen.insert(output.id.clone()) {
+                    output.id = StandardId::generate();
+                }
+            }
+        }
+
+        let outputs = self
+            .nodes
+            .iter()
+            .flat_map(|node| node.outputs.iter().map(|output| output.id.clone()))
+            .collect::<HashSet<_>>();
+        let inputs = self
+            .nodes
+            .iter()
+            .flat_map(|node| node.inputs.iter().map(|input| input.id.clone()))
+            .collect::<HashSet<_>>();
+        self.edges.retain(|edge| {
+            let from = edge.from_output.as_ref();
+            let to = edge.to_input.as_ref();
+            from.map(|id| outputs.contains(id)).unwrap_or_default()
+                && to.map(|id| inputs.contains(id)).unwrap_or_default()
+        });
+
+        let node_ids = self
+            .nodes
+            .iter()
+            .map(|node| node.id.clone())
+            .collect::<HashSet<_>>();
+        for group in self.groups.iter_mut() {
+            group.children.retain(|id| node_ids.contains(id));
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{
+        edge::models::Edge,
+        group::models::Group,
+        node::models::{NodeInput, NodeOutput},
+    };
+
+    /// Node with connectors "{id}-in" and "{id}-out", placed at `x`.
+    fn node(id: &str, x: f64) -> Node {
+        Node {
+            id: id.to_string(),
+            kind: None,
+            title: id.to_string(),
+            x,
+            y: 0.,
+            color: "#000000".to_string(),
+            inputs: vec![NodeInput::new(format!("{}-in", id))],
+            outputs: vec![NodeOutput::new(format!("{}-out", id))],
+            subflow: None,
+            collapsed: false,
+            fields: Vec::new(),
+            values: Default::default(),
+            status: Default::default(),
+            progress: None,
+        }
+    }
+
+    fn edge(id: &str, from_output: Option<&str>, to_input: Option<&str>) -> Edge {
+        Edge {
+            id: id.to_string(),
+            from_output: from_output.map(str::to_string),
+            to_input: to_input.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn group(id: &str, children: &[&str]) -> Group {
+        Group {
+            id: id.to_string(),
+            title: id.to_string(),
+            color: "#64748b".to_string(),
+            x: 0.,
+            y: 0.,
+            width: 100.,
+            height: 100.,
+            collapsed: false,
+            children: children.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    fn values(nodes: Vec<Node>, edges: Vec<Edge>) -> YewFlowValues {
+        YewFlowValues {
+            nodes,
+            edges,
+            groups: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn valid_values_have_no_diagnostics() {
+        let mut values = values(
+            // touching nodes don't overlap
+            vec![node("a", 0.), node("b", NODE_WIDTH)],
+            vec![edge("a-b", Some("a-out"), Some("b-in"))],
+        );
+        values.groups.push(group("g", &["a", "b"]));
+        assert_eq!(values.validate(), Vec::new());
+    }
+
+    #[test]
+    fn reports_duplicate_ids() {
+        let mut other_a = node("x", 400.);
+        other_a.id = "a".to_string();
+        let mut c = node("c", 600.);
+        c.inputs[0].id = "b-in".to_string();
+        let mut values = values(
+            vec![node("a", 0.), node("b", 200.), other_a, c],
+            vec![
+                edge("e", Some("a-out"), Some("b-in")),
+                edge("e", Some("x-out"), Some("b-in")),
+            ],
+        );
+        values.groups = vec![group("g", &[]), group("g", &[])];
+        assert_eq!(
+            values.validate(),
+            vec![
+                Diagnostic::DuplicateId {
+                    kind: ItemKind::Node,
+                    id: "a".to_string(),
+                },
+                Diagnostic::DuplicateId {
+                    kind: ItemKind::Port,
+                    id: "b-in".to_string(),
+                },
+                Diagnostic::DuplicateId {
+                    kind: ItemKind::Edge,
+                    id: "e".to_string(),
+                },
+                Diagnostic::DuplicateId {
+                    kind: ItemKind::Group,
+                    id: "g".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_dangling_ports() {
+        let values = values(
+            vec![node("a", 0.), node("b", 200.)],
+            vec![
+                edge("from-unknown", Some("unknown-out"), Some("b-in")),
+                // an input is not an output
+                edge("to-unknown", Some("a-out"), Some("a-out")),
+            ],
+        );
+        assert_eq!(
+            values.validate(),
+            vec![
+                Diagnostic::DanglingOutput {
+                    edge_id: "from-unknown".to_string(),
+                    port_id: "unknown-out".to_string(),
+                },
+                Diagnostic::DanglingInput {
+                    edge_id: "to-unknown".to_string(),
+                    port_id: "a-out".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_half_connected_edges() {
+        let values = values(
+            vec![node("a", 0.)],
+            vec![
+                edge("loose", Some("a-out"), None),
+                edge("empty", None, None),
+            ],
+        );
+        assert_eq!(
+            values.validate(),
+            vec![
+                Diagnostic::HalfConnectedEdge {
+                    edge_id: "loose".to_string(),
+                },
+                Diagnostic::HalfConnectedEdge {
+                    edge_id: "empty".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_dangling_group_children() {
+        let mut values = values(vec![node("a", 0.)], Vec::new());
+        values.groups.push(group("g", &["a", "gone"]));
+        assert_eq!(
+            values.validate(),
+            vec![Diagnostic::DanglingGroupChild {
+                group_id: "g".to_string(),
+                node_id: "gone".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_overlaps_as_warnings() {
+        let values = values(vec![node("a", 0.), node("b", NODE_WIDTH / 2.)], Vec::new());
+        let diagnostics = values.validate();
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::OverlappingNodes {
+                a: "a".to_string(),
+                b: "b".to_string(),
+            }]
+        );
+        assert!(!diagnostics[0].is_error());
+    }
+
+    #[test]
+    fn repair_fixes_errors_and_keeps_overlaps() {
+        let mut c = node("c", 10.);
+        c.inputs[0].id = "b-in".to_string();
+        let mut values = values(
+            vec![node("a", 0.), node("b", 200.), c],
+            vec![
+                edge("a-b", Some("a-out"), Some("b-in")),
+                edge("a-b", Some("a-out"), Some("c-in")),
+                edge("dangling", Some("a-out"), Some("unknown-in")),
+                edge("loose", Some("a-out"), None),
+            ],
+        );
+        values.groups.push(group("g", &["a", "gone"]));
+        let expected = values.validate();
+
+        assert_eq!(values.repair(), expected);
+        // the first connector keeps the id, the second gets a new one
+        assert_eq!(values.nodes[1].inputs[0].id, "b-in");
+        assert_ne!(values.nodes[2].inputs[0].id, "b-in");
+        assert_eq!(values.edges.len(), 1);
+        assert_eq!(values.edges[0].to_input.as_deref(), Some("b-in"));
+        assert_eq!(values.groups[0].children, vec!["a"]);
+        // only the overlap of a and c is left
+        assert_eq!(
+            values.validate(),
+            vec![Diagnostic::OverlappingNodes {
+                a: "a".to_string(),
+                b: "c".to_string(),
+            }]
+        );
+        assert_eq!((values.nodes[2].x, values.nodes[2].y), (10., 0.));
+    }
+}
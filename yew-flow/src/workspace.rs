@@ -1,26 +1,97 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{ClipboardEvent, Element};
 use yew::prelude::*;
 
 use crate::{
+    clipboard::{copy_subgraph, parse_clipboard_text_to_values, values_to_clipboard_text},
     components::{
-        edge::{models::Edge, render_edge_list::RenderEdgeList},
-        node::{models::Node, render_node_list::RenderNodeList},
+        comment::{models::Comment, render_comment_list::RenderCommentList},
+        context_menu::{
+            models::{
+                ContextMenu, ContextMenuAction, ContextMenuItem, ContextMenuItems,
+                ContextMenuSelectEvent, ContextMenuTarget,
+            },
+            render_context_menu::RenderContextMenu,
+        },
+        edge::{
+            models::{Edge, EdgeActivity},
+            render_edge_list::RenderEdgeList,
+        },
+        group::{models::Group, render_group_list::RenderGroupList},
+        node::{
+            models::{Node, NodeStatus, NodeTemplate},
+            node_palette::NODE_TEMPLATE_MIME_TYPE,
+            render_node_list::RenderNodeList,
+        },
+        quick_add::render_quick_add::RenderQuickAdd,
+        subflow::render_breadcrumb::RenderBreadcrumb,
         viewport::models::Viewport,
     },
-    constants::{NODE_HEIGHT, NODE_WIDTH},
+    constants::{CONNECTION_RADIUS, NODE_HEIGHT, NODE_WIDTH, WHEEL_ZOOM_SPEED},
+    graph::{GraphIndex, HighlightMode},
     store::{
-        DragEdgeCmd, DragNodeCmd, InteractionMode, NewEdgeDragDeactivateCmd, WorkspaceAction,
-        WorkspaceStore,
+        attached_edge_ids, Connector, ContextMenuOpenCmd, DragCommentCmd, DragEdgeCmd,
+        DragGroupCmd, DragNodeCmd, EdgeActivityCmd, InteractionMode, NewEdgeDragDeactivateCmd,
+        NodeSelectCmd, NodeStatusCmd, PanCmd, PinchCmd, QuickAddOpenCmd, WorkspaceAction,
+        WorkspaceStore, ZoomCmd,
     },
+    types::{standard_id::StandardId, standard_unit::StandardUnit},
+    utils::capture_pointer_on,
+    validate::{Diagnostic, IntegrityPolicy},
 };
 
 /// # Initial State
 ///
 /// Initial state of the workspace.
 #[derive(Debug, Clone, Properties, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct YewFlowValues {
     pub nodes: Vec<Node>,
     pub edges: Vec<Edge>,
+    /// Groups/frames containing nodes.
+    #[serde(default)]
+    #[prop_or_default]
+    pub groups: Vec<Group>,
+    /// Comments/sticky notes on the canvas.
+    #[serde(default)]
+    #[prop_or_default]
+    pub comments: Vec<Comment>,
+}
+
+/// Put the selected nodes/comments and the edges between them on the clipboard.
+/// Returns `true` when something was copied.
+fn copy_selection_to_clipboard(
+    e: &Event,
+    nodes: &[Node],
+    edges: &[Edge],
+    comments: &[Comment],
+    selection: &[StandardId],
+) -> bool {
+    if selection.is_empty() {
+        return false;
+    }
+    let data = match e
+        .dyn_ref::<ClipboardEvent>()
+        .and_then(|e| e.clipboard_data())
+    {
+        Some(data) => data,
+        None => return false,
+    };
+    let values = copy_subgraph(nodes, edges, comments, selection);
+    match values_to_clipboard_text(&values) {
+        Ok(text) if data.set_data("text/plain", &text).is_ok() => {
+            e.prevent_default();
+            true
+        }
+        Ok(_) => false,
+        Err(err) => {
+            log::error!("failed to copy selection: {}", err);
+            false
+        }
+    }
 }
 
 #[derive(Debug, Clone, Properties, PartialEq, Serialize, Deserialize)]
@@ -29,6 +100,108 @@ pub struct WorkspaceProps {
     pub prevent_changes: bool,
     #[serde(skip)]
     pub on_change: Callback<YewFlowValues>,
+    /// Radius within which a dragged edge snaps to the closest
+    /// compatible connector. Defaults to `CONNECTION_RADIUS`.
+    /// Dropping right on a connector connects with any radius.
+    #[serde(default)]
+    #[prop_or_default]
+    pub connection_radius: Option<StandardUnit>,
+    /// Called when an edge is clicked.
+    #[serde(skip)]
+    #[prop_or_default]
+    pub on_edge_click: Callback<Edge>,
+    /// Called when an edge is double clicked.
+    #[serde(skip)]
+    #[prop_or_default]
+    pub on_edge_double_click: Callback<Edge>,
+    /// Extra context menu items per target, listed after the built-in ones.
+    #[serde(default)]
+    #[prop_or_default]
+    pub context_menu_items: ContextMenuItems,
+    /// Called when a custom context menu item is chosen.
+    #[serde(skip)]
+    #[prop_or_default]
+    pub on_context_menu_select: Callback<ContextMenuSelectEvent>,
+    /// Templates offered by the quick-add popup. The popup opens with
+    /// the `/` key or when a new edge is dropped on empty canvas.
+    #[serde(default)]
+    #[prop_or_default]
+    pub node_templates: Vec<NodeTemplate>,
+    /// Reject edges that would close a cycle, e.g. for pipelines
+    /// that must stay a DAG. The reason is shown to the user.
+    #[serde(default)]
+    #[prop_or_default]
+    pub acyclic: bool,
+    /// Dim everything but the connections of the hovered or selected
+    /// node, or the path between two selected nodes.
+    #[serde(default)]
+    #[prop_or_default]
+    pub highlight_mode: HighlightMode,
+    /// What to do with `values` that refer to missing nodes/connectors
+    /// or contain duplicate ids when they are loaded.
+    #[serde(default)]
+    #[prop_or_default]
+    pub integrity_policy: IntegrityPolicy,
+    /// Called with the problems found in `values` when they are loaded,
+    /// unless the policy is `IntegrityPolicy::Ignore`.
+    #[serde(skip)]
+    #[prop_or_default]
+    pub on_diagnostics: Callback<Vec<Diagnostic>>,
+}
+
+/// Apply `policy` to `values` that are about to be loaded. Returns the values
+/// to load, or `None` when they are refused, and the problems found.
+fn check_values(
+    values: &YewFlowValues,
+    policy: IntegrityPolicy,
+) -> (Option<YewFlowValues>, Vec<Diagnostic>) {
+    match policy {
+        IntegrityPolicy::Ignore => (Some(values.clone()), Vec::new()),
+        IntegrityPolicy::Repair => {
+            let mut values = values.clone();
+            let diagnostics = values.repair();
+            (Some(values), diagnostics)
+        }
+        IntegrityPolicy::Refuse => {
+            let diagnostics = values.validate();
+            if diagnostics.iter().any(Diagnostic::is_error) {
+                (None, diagnostics)
+            } else {
+                (Some(values.clone()), diagnostics)
+            }
+        }
+    }
+}
+
+/// `values` without the edge activity and node status that are synced into
+/// the store by their own effects, so that changing only them doesn't re-init
+/// the workspace.
+fn structure_of(values: &YewFlowValues) -> YewFlowValues {
+    let mut values = values.clone();
+    for node in values.nodes.iter_mut() {
+        node.status = NodeStatus::Idle;
+        node.progress = None;
+    }
+    for edge in values.edges.iter_mut() {
+        edge.animated = false;
+        edge.activity = EdgeActivity::Idle;
+    }
+    values
+}
+
+/// Get the pinch gesture state when exactly two pointers are pressed.
+fn pinch_from_pointers(pointers: &HashMap<i32, (StandardUnit, StandardUnit)>) -> Option<PinchCmd> {
+    if pointers.len() != 2 {
+        return None;
+    }
+    let mut positions = pointers.values();
+    let (x1, y1) = positions.next()?;
+    let (x2, y2) = positions.next()?;
+    Some(PinchCmd {
+        distance: (x2 - x1).hypot(y2 - y1),
+        center_x: (x1 + x2) / 2.,
+        center_y: (y1 + y2) / 2.,
+    })
 }
 
 /// # Yew Flow Workspace
@@ -41,26 +214,123 @@ pub fn workspace(
         values,
         prevent_changes,
         on_change,
+        connection_radius,
+        on_edge_click,
+        on_edge_double_click,
+        context_menu_items,
+        on_context_menu_select,
+        node_templates,
+        acyclic,
+        highlight_mode,
+        integrity_policy,
+        on_diagnostics,
     }: &WorkspaceProps,
 ) -> Html {
     let container_ref = use_node_ref();
-    let store = use_reducer(|| WorkspaceStore {
-        nodes: values.nodes.clone(),
-        edges: values.edges.clone(),
-        ..Default::default()
+    let store = use_reducer(|| {
+        // refused values leave the workspace empty
+        let values = check_values(values, *integrity_policy)
+            .0
+            .unwrap_or_else(|| YewFlowValues {
+                nodes: Vec::new(),
+                edges: Vec::new(),
+                groups: Vec::new(),
+                comments: Vec::new(),
+            });
+        WorkspaceStore {
+            graph: GraphIndex::new(&values.nodes, &values.edges),
+            nodes: values.nodes,
+            edges: values.edges,
+            groups: values.groups,
+            comments: values.comments,
+            acyclic: *acyclic,
+            ..Default::default()
+        }
     });
     let dispatcher = store.dispatcher();
 
-    let on_container_mouse_move = {
+    // pan/zoom of the flow
+    let transform = store
+        .viewport
+        .as_ref()
+        .map(|viewport| viewport.transform.clone())
+        .unwrap_or_default();
+    // pressed pointers (by pointer id) with positions relative to the viewport
+    let pointers = use_mut_ref(HashMap::<i32, (StandardUnit, StandardUnit)>::new);
+    // last absolute pointer position, used to place the quick-add popup
+    let cursor = use_mut_ref(|| (0., 0.));
+
+    let on_container_pointer_down = {
         let container_ref = container_ref.clone();
         let dispatcher = dispatcher.clone();
-        let interaction_mode = store.interaction_mode.clone();
+        let pointers = pointers.clone();
         use_callback(
-            move |e: MouseEvent, (container_ref, dispatcher, interaction_mode)| {
+            // Only receives pointers on the background and secondary pointers,
+            // nodes and connectors handle their primary pointers themselves.
+            move |e: PointerEvent, (container_ref, dispatcher)| {
                 let viewport = Viewport::new(container_ref.clone());
+                let x = viewport.viewport_x_from_abs(e.page_x().into());
+                let y = viewport.viewport_y_from_abs(e.page_y().into());
+                if let Some(container) = container_ref.cast::<Element>() {
+                    capture_pointer_on(&container, &e);
+                }
+                let mut pointers = pointers.borrow_mut();
+                pointers.insert(e.pointer_id(), (x, y));
+                if let Some(pinch) = pinch_from_pointers(&pointers) {
+                    dispatcher.dispatch(WorkspaceAction::PinchActivate(pinch))
+                } else if e.is_primary() {
+                    dispatcher.dispatch(WorkspaceAction::PanActivate(PanCmd { x, y }))
+                }
+            },
+            (container_ref, dispatcher),
+        )
+    };
+    let on_container_pointer_move =
+        {
+            let container_ref = container_ref.clone();
+            let dispatcher = dispatcher.clone();
+            let interaction_mode = store.interaction_mode.clone();
+            let connection_radius = connection_radius.unwrap_or(CONNECTION_RADIUS);
+            let transform = transform.clone();
+            let pointers = pointers.clone();
+            let cursor = cursor.clone();
+            use_callback(
+            move |e: PointerEvent,
+                  (container_ref, dispatcher, interaction_mode, radius, transform)| {
+                if e.is_primary() {
+                    *cursor.borrow_mut() =
+                        (StandardUnit::from(e.page_x()), StandardUnit::from(e.page_y()));
+                }
+                let viewport =
+                    Viewport::new(container_ref.clone()).with_transform(transform.clone());
                 if viewport.dimensions.width > 0. && viewport.dimensions.height > 0. {
+                    let viewport_x = viewport.viewport_x_from_abs(e.page_x().into());
+                    let viewport_y = viewport.viewport_y_from_abs(e.page_y().into());
+                    let pinch = {
+                        let mut pointers = pointers.borrow_mut();
+                        // only track pressed pointers
+                        if e.buttons() > 0 {
+                            pointers.insert(e.pointer_id(), (viewport_x, viewport_y));
+                        }
+                        pinch_from_pointers(&pointers)
+                    };
+                    if let Some(pinch) = pinch {
+                        match interaction_mode {
+                            InteractionMode::Pinch(_) => {
+                                dispatcher.dispatch(WorkspaceAction::Pinch(pinch))
+                            }
+                            _ => dispatcher.dispatch(WorkspaceAction::PinchActivate(pinch)),
+                        }
+                        return;
+                    }
+                    if !e.is_primary() {
+                        return;
+                    }
                     match interaction_mode {
-                        InteractionMode::None => {
+                        InteractionMode::None
+                        | InteractionMode::EdgeHover(_)
+                        | InteractionMode::Pinch(_)
+                        | InteractionMode::KeyboardConnect(_) => {
                             // dispatcher.dispatch(WorkspaceAction::DragNode(DragNodeCmd { x, y }))
                         }
                         InteractionMode::NodeDrag(_) => {
@@ -70,24 +340,68 @@ pub fn workspace(
                                 .relative_y_pos_from_abs(e.page_y().into(), Some(NODE_HEIGHT));
                             dispatcher.dispatch(WorkspaceAction::NodeDrag(DragNodeCmd { x, y }))
                         }
-                        InteractionMode::NewEdgeDrag(_) => {
+                        InteractionMode::NewEdgeDrag(_) | InteractionMode::EdgeUpdate(_) => {
                             let x = viewport.relative_x_pos_from_abs(e.page_x().into(), None);
                             let y = viewport.relative_y_pos_from_abs(e.page_y().into(), None);
-                            dispatcher.dispatch(WorkspaceAction::EdgeDrag(DragEdgeCmd { x, y }))
+                            dispatcher.dispatch(WorkspaceAction::EdgeDrag(DragEdgeCmd {
+                                x,
+                                y,
+                                connection_radius: *radius,
+                            }))
+                        }
+                        InteractionMode::Pan(..) => {
+                            dispatcher.dispatch(WorkspaceAction::Pan(PanCmd {
+                                x: viewport_x,
+                                y: viewport_y,
+                            }))
+                        }
+                        InteractionMode::GroupDrag(..) => {
+                            dispatcher.dispatch(WorkspaceAction::GroupDrag(DragGroupCmd {
+                                x: e.page_x().into(),
+                                y: e.page_y().into(),
+                            }))
+                        }
+                        InteractionMode::CommentDrag(..) | InteractionMode::CommentResize(..) => {
+                            dispatcher.dispatch(WorkspaceAction::CommentDrag(DragCommentCmd {
+                                x: e.page_x().into(),
+                                y: e.page_y().into(),
+                            }))
                         }
                     }
                 }
             },
-            (container_ref, dispatcher, interaction_mode),
+            (
+                container_ref,
+                dispatcher,
+                interaction_mode,
+                connection_radius,
+                transform,
+            ),
         )
-    };
-    let on_container_mouse_up = {
+        };
+    let on_container_pointer_up = {
         let dispatcher = dispatcher.clone();
         let interaction_mode = store.interaction_mode.clone();
+        let pointers = pointers.clone();
+        let open_quick_add = !node_templates.is_empty();
         use_callback(
-            move |_: MouseEvent, (dispatcher, interaction_mode)| {
+            move |e: PointerEvent, (dispatcher, interaction_mode, open_quick_add)| {
+                let remaining = {
+                    let mut pointers = pointers.borrow_mut();
+                    pointers.remove(&e.pointer_id());
+                    pointers.len()
+                };
                 match interaction_mode {
-                    InteractionMode::None => {
+                    InteractionMode::Pinch(_) => {
+                        if remaining < 2 {
+                            dispatcher.dispatch(WorkspaceAction::PinchDeactivate)
+                        }
+                    }
+                    // secondary pointers never drive other interactions
+                    _ if !e.is_primary() => {}
+                    InteractionMode::None
+                    | InteractionMode::EdgeHover(_)
+                    | InteractionMode::KeyboardConnect(_) => {
                         // dispatch(WorkspaceAction::DragNode(DragNodeCmd { x, y }))
                     }
                     InteractionMode::NodeDrag(_) => {
@@ -97,11 +411,286 @@ pub fn workspace(
                         WorkspaceAction::NewEdgeDragDeactivate(NewEdgeDragDeactivateCmd {
                             to_reference: None,
                             to_connector: None,
+                            open_quick_add: *open_quick_add,
                         }),
                     ),
+                    InteractionMode::EdgeUpdate(_) => dispatcher.dispatch(
+                        WorkspaceAction::EdgeUpdateDeactivate(NewEdgeDragDeactivateCmd {
+                            to_reference: None,
+                            to_connector: None,
+                            open_quick_add: false,
+                        }),
+                    ),
+                    InteractionMode::Pan(..) => dispatcher.dispatch(WorkspaceAction::PanDeactivate),
+                    InteractionMode::GroupDrag(..) => {
+                        dispatcher.dispatch(WorkspaceAction::GroupDragDeactivate)
+                    }
+                    InteractionMode::CommentDrag(..) | InteractionMode::CommentResize(..) => {
+                        dispatcher.dispatch(WorkspaceAction::CommentDragDeactivate)
+                    }
+                }
+            },
+            (dispatcher, interaction_mode, open_quick_add),
+        )
+    };
+    let on_container_wheel = {
+        let container_ref = container_ref.clone();
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |e: WheelEvent, (container_ref, dispatcher)| {
+                let viewport = Viewport::new(container_ref.clone());
+                dispatcher.dispatch(WorkspaceAction::Zoom(ZoomCmd {
+                    factor: (-e.delta_y() * WHEEL_ZOOM_SPEED).exp(),
+                    x: viewport.viewport_x_from_abs(e.page_x().into()),
+                    y: viewport.viewport_y_from_abs(e.page_y().into()),
+                }))
+            },
+            (container_ref, dispatcher),
+        )
+    };
+
+    let on_container_key_down = {
+        let dispatcher = dispatcher.clone();
+        let cursor = cursor.clone();
+        use_callback(
+            move |e: KeyboardEvent, (dispatcher, has_templates)| {
+                if e.key() == "Escape" {
+                    dispatcher.dispatch(WorkspaceAction::ContextMenuClose);
+                    dispatcher.dispatch(WorkspaceAction::QuickAddClose);
+                    dispatcher.dispatch(WorkspaceAction::KeyboardConnectCancel);
+                    dispatcher.dispatch(WorkspaceAction::SelectionClear);
+                } else if (e.ctrl_key() || e.meta_key()) && e.key().eq_ignore_ascii_case("d") {
+                    // keep the browser from bookmarking the page
+                    e.prevent_default();
+                    dispatcher.dispatch(WorkspaceAction::DuplicateSelection);
+                } else if (e.ctrl_key() || e.meta_key()) && e.key().eq_ignore_ascii_case("g") {
+                    e.prevent_default();
+                    dispatcher.dispatch(WorkspaceAction::GroupSelection);
+                } else if e.key() == "/" && *has_templates {
+                    e.prevent_default();
+                    let (x, y) = *cursor.borrow();
+                    dispatcher.dispatch(WorkspaceAction::QuickAddOpen(QuickAddOpenCmd { x, y }));
+                }
+            },
+            (dispatcher, !node_templates.is_empty()),
+        )
+    };
+    let on_quick_add_close = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |_: (), dispatcher| dispatcher.dispatch(WorkspaceAction::QuickAddClose),
+            dispatcher,
+        )
+    };
+    let on_quick_add_select = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |template: NodeTemplate, dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::QuickAddSelect(template))
+            },
+            dispatcher,
+        )
+    };
+    let on_container_context_menu = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            // nodes, connectors and edges open their own menus
+            move |e: MouseEvent, dispatcher| {
+                e.prevent_default();
+                dispatcher.dispatch(WorkspaceAction::ContextMenuOpen(ContextMenuOpenCmd {
+                    target: ContextMenuTarget::Background,
+                    x: e.page_x().into(),
+                    y: e.page_y().into(),
+                }))
+            },
+            dispatcher,
+        )
+    };
+    let on_context_menu_close = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |_: (), dispatcher| dispatcher.dispatch(WorkspaceAction::ContextMenuClose),
+            dispatcher,
+        )
+    };
+    let on_context_menu_item_select = {
+        let dispatcher = dispatcher.clone();
+        let on_context_menu_select = on_context_menu_select.clone();
+        use_callback(
+            move |item: ContextMenuItem,
+                  (dispatcher, on_context_menu_select, nodes, edges, selection, context_menu)| {
+                dispatcher.dispatch(WorkspaceAction::ContextMenuClose);
+                let (target, x, y) = match context_menu {
+                    Some(ContextMenu { target, x, y }) => (target.clone(), *x, *y),
+                    None => return,
+                };
+                // node actions apply to the whole selection when the node is part of it
+                let node_ids = |id: &StandardId| {
+                    if selection.contains(id) {
+                        selection.clone()
+                    } else {
+                        vec![id.clone()]
+                    }
+                };
+                match (item.action, &target) {
+                    (ContextMenuAction::Custom(id), _) => {
+                        on_context_menu_select.emit(ContextMenuSelectEvent {
+                            id,
+                            target: target.clone(),
+                        })
+                    }
+                    (ContextMenuAction::Delete, ContextMenuTarget::Node(id)) => {
+                        dispatcher.dispatch(WorkspaceAction::NodesDelete(node_ids(id)))
+                    }
+                    (ContextMenuAction::Delete, ContextMenuTarget::Edge(id)) => {
+                        dispatcher.dispatch(WorkspaceAction::EdgesDelete(vec![id.clone()]))
+                    }
+                    (ContextMenuAction::Duplicate, ContextMenuTarget::Node(id)) => {
+                        if !selection.contains(id) {
+                            dispatcher.dispatch(WorkspaceAction::NodeSelect(NodeSelectCmd {
+                                id: id.clone(),
+                                additive: false,
+                            }));
+                        }
+                        dispatcher.dispatch(WorkspaceAction::DuplicateSelection)
+                    }
+                    (ContextMenuAction::CollapseToSubflow, ContextMenuTarget::Node(id)) => {
+                        if !selection.contains(id) {
+                            dispatcher.dispatch(WorkspaceAction::NodeSelect(NodeSelectCmd {
+                                id: id.clone(),
+                                additive: false,
+                            }));
+                        }
+                        dispatcher.dispatch(WorkspaceAction::SubflowCreate)
+                    }
+                    (ContextMenuAction::DisconnectAll, ContextMenuTarget::Node(id)) => {
+                        let port_ids = nodes
+                            .iter()
+                            .filter(|node| node.id == *id)
+                            .flat_map(|node| node.port_ids())
+                            .collect::<Vec<_>>();
+                        dispatcher.dispatch(WorkspaceAction::EdgesDelete(attached_edge_ids(
+                            edges, &port_ids,
+                        )))
+                    }
+                    (
+                        ContextMenuAction::DisconnectAll,
+                        ContextMenuTarget::Port(Connector::Input(id) | Connector::Output(id)),
+                    ) => dispatcher.dispatch(WorkspaceAction::EdgesDelete(attached_edge_ids(
+                        edges,
+                        &[id.clone()],
+                    ))),
+                    (ContextMenuAction::AddComment, ContextMenuTarget::Background) => {
+                        dispatcher.dispatch(WorkspaceAction::AddComment(Comment::new(x, y)))
+                    }
+                    (action, target) => {
+                        log::warn!("{:?} is not supported on {:?}", action, target)
+                    }
+                }
+            },
+            (
+                dispatcher,
+                on_context_menu_select,
+                store.nodes.clone(),
+                store.edges.clone(),
+                store.selection.clone(),
+                store.context_menu.clone(),
+            ),
+        )
+    };
+    let on_container_drag_over = use_callback(
+        move |e: DragEvent, _| {
+            let is_template = e
+                .data_transfer()
+                .map(|data| data.types().includes(&NODE_TEMPLATE_MIME_TYPE.into(), 0))
+                .unwrap_or_default();
+            if is_template {
+                // allow dropping
+                e.prevent_default();
+            }
+        },
+        (),
+    );
+    let on_container_drop = {
+        let container_ref = container_ref.clone();
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |e: DragEvent, (container_ref, dispatcher, transform)| {
+                let json = e
+                    .data_transfer()
+                    .and_then(|data| data.get_data(NODE_TEMPLATE_MIME_TYPE).ok())
+                    .filter(|json| !json.is_empty());
+                let json = match json {
+                    Some(json) => json,
+                    None => return,
+                };
+                e.prevent_default();
+                match serde_json::from_str::<NodeTemplate>(&json) {
+                    Ok(template) => {
+                        let viewport =
+                            Viewport::new(container_ref.clone()).with_transform(transform.clone());
+                        // center the node on the drop position
+                        let x =
+                            viewport.relative_x_pos_from_abs(e.page_x().into(), Some(NODE_WIDTH));
+                        let y =
+                            viewport.relative_y_pos_from_abs(e.page_y().into(), Some(NODE_HEIGHT));
+                        dispatcher.dispatch(WorkspaceAction::AddNode(template.instantiate(x, y)))
+                    }
+                    Err(err) => log::error!("failed to drop node template: {}", err),
+                }
+            },
+            (container_ref, dispatcher, transform.clone()),
+        )
+    };
+    let on_container_copy = use_callback(
+        move |e: Event, (nodes, edges, comments, selection)| {
+            copy_selection_to_clipboard(&e, nodes, edges, comments, selection);
+        },
+        (
+            store.nodes.clone(),
+            store.edges.clone(),
+            store.comments.clone(),
+            store.selection.clone(),
+        ),
+    );
+    let on_container_cut = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |e: Event, (nodes, edges, comments, selection, dispatcher)| {
+                if copy_selection_to_clipboard(&e, nodes, edges, comments, selection) {
+                    dispatcher.dispatch(WorkspaceAction::NodesDelete(selection.clone()));
+                }
+            },
+            (
+                store.nodes.clone(),
+                store.edges.clone(),
+                store.comments.clone(),
+                store.selection.clone(),
+                dispatcher,
+            ),
+        )
+    };
+    let on_container_paste = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |e: Event, dispatcher| {
+                let text = e
+                    .dyn_ref::<ClipboardEvent>()
+                    .and_then(|e| e.clipboard_data())
+                    .and_then(|data| data.get_data("text/plain").ok());
+                if let Some(text) = text {
+                    match parse_clipboard_text_to_values(&text) {
+                        Ok(values) if !values.nodes.is_empty() || !values.comments.is_empty() => {
+                            e.prevent_default();
+                            dispatcher.dispatch(WorkspaceAction::Paste(values));
+                        }
+                        Ok(_) => {}
+                        // not a flow, let the browser handle it
+                        Err(err) => log::debug!("ignoring pasted text: {}", err),
+                    }
                 }
             },
-            (dispatcher, interaction_mode),
+            dispatcher,
         )
     };
 
@@ -109,19 +698,88 @@ pub fn workspace(
         let dispatcher = dispatcher.clone();
         let values = values.clone();
         let prevent_changes = prevent_changes.clone();
+        let on_diagnostics = on_diagnostics.clone();
+        let structure = structure_of(&values);
         use_effect_with_deps(
-            // Re-run this on every change of container_ref
-            move |(values, prevent_changes, dispatcher)| {
+            // Re-run this on every change of the flow structure
+            move |(_, prevent_changes, integrity_policy, dispatcher)| {
                 if *prevent_changes {
-                    // Re-init the workspace with changed
-                    dispatcher.dispatch(WorkspaceAction::Init(Some(values.clone())));
+                    let (checked, diagnostics) = check_values(&values, *integrity_policy);
+                    match checked {
+                        // Re-init the workspace with changed
+                        Some(values) => dispatcher.dispatch(WorkspaceAction::Init(Some(values))),
+                        None => dispatcher.dispatch(WorkspaceAction::Notice(format!(
+                            "Refused to load the flow, {}",
+                            diagnostics
+                                .iter()
+                                .find(|diagnostic| diagnostic.is_error())
+                                .map(|diagnostic| diagnostic.to_string())
+                                .unwrap_or_default(),
+                        ))),
+                    }
+                    if !diagnostics.is_empty() {
+                        on_diagnostics.emit(diagnostics);
+                    }
                 } else {
                     // Re-init the workspace with default values
                     dispatcher.dispatch(WorkspaceAction::Init(None));
                 }
                 || ()
             },
-            (values, prevent_changes, dispatcher),
+            (structure, prevent_changes, *integrity_policy, dispatcher),
+        )
+    }
+
+    {
+        let dispatcher = dispatcher.clone();
+        let activities = values
+            .edges
+            .iter()
+            .map(|edge| EdgeActivityCmd {
+                id: edge.id.clone(),
+                animated: edge.animated,
+                activity: edge.activity.clone(),
+            })
+            .collect::<Vec<_>>();
+        use_effect_with_deps(
+            // Sync edge animation/activity from values without re-init of the store
+            move |(activities, dispatcher)| {
+                dispatcher.dispatch(WorkspaceAction::EdgeActivityChange(activities.clone()));
+                || ()
+            },
+            (activities, dispatcher),
+        )
+    }
+
+    {
+        let dispatcher = dispatcher.clone();
+        use_effect_with_deps(
+            move |(acyclic, dispatcher)| {
+                dispatcher.dispatch(WorkspaceAction::AcyclicChange(*acyclic));
+                || ()
+            },
+            (*acyclic, dispatcher),
+        )
+    }
+
+    {
+        let dispatcher = dispatcher.clone();
+        let statuses = values
+            .nodes
+            .iter()
+            .map(|node| NodeStatusCmd {
+                id: node.id.clone(),
+                status: node.status.clone(),
+                progress: node.progress,
+            })
+            .collect::<Vec<_>>();
+        use_effect_with_deps(
+            // Sync node execution states from values without re-init of the store
+            move |(statuses, dispatcher)| {
+                dispatcher.dispatch(WorkspaceAction::NodeStatusChange(statuses.clone()));
+                || ()
+            },
+            (statuses, dispatcher),
         )
     }
 
@@ -142,25 +800,141 @@ pub fn workspace(
     }
 
     {
+        let dispatcher = dispatcher.clone();
         let nodes = store.nodes.clone();
-        let edges = store.edges.clone();
+        use_effect_with_deps(
+            // Re-read edge coordinates once moved/added nodes are rendered
+            move |(_, dispatcher)| {
+                dispatcher.dispatch(WorkspaceAction::RefreshEdgePositions);
+                || ()
+            },
+            (nodes, dispatcher),
+        )
+    }
+
+    {
+        // the whole hierarchy, also while a subflow is open
+        let values = store.root_values();
         let on_change = on_change.clone();
         let prevent_changes = prevent_changes.clone();
         use_effect_with_deps(
-            // Re-run this on every change of nodes/edges to send the new values back to parent
-            |(nodes, edges, on_change, prevent_changes)| {
+            // Re-run this on every change of the flow to send the new values back to parent
+            |(values, on_change, prevent_changes)| {
                 if !prevent_changes {
-                    on_change.emit(YewFlowValues {
-                        nodes: nodes.clone(),
-                        edges: edges.clone(),
-                    });
+                    on_change.emit(values.clone());
                 }
                 || ()
             },
-            (nodes, edges, on_change, prevent_changes),
+            (values, on_change, prevent_changes),
         )
     }
 
+    // children of collapsed groups are hidden together with their edges
+    let hidden_nodes = store
+        .groups
+        .iter()
+        .filter(|group| group.collapsed)
+        .flat_map(|group| group.children.iter())
+        .collect::<Vec<_>>();
+    let visible_nodes = store
+        .nodes
+        .iter()
+        .filter(|node| !hidden_nodes.contains(&&node.id))
+        .cloned()
+        .collect::<Vec<_>>();
+    let hidden_edges = attached_edge_ids(
+        &store.edges,
+        &store
+            .nodes
+            .iter()
+            .filter(|node| hidden_nodes.contains(&&node.id))
+            .flat_map(|node| node.port_ids())
+            .collect::<Vec<_>>(),
+    );
+    let visible_edges = store
+        .edges
+        .iter()
+        .filter(|edge| !hidden_edges.contains(&edge.id))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let on_breadcrumb_navigate = {
+        let dispatcher = dispatcher.clone();
+        use_callback(
+            move |depth: usize, dispatcher| {
+                dispatcher.dispatch(WorkspaceAction::SubflowExit(depth))
+            },
+            dispatcher,
+        )
+    };
+    let render_breadcrumb = if store.subflow_path.is_empty() {
+        html! {}
+    } else {
+        html! {
+            <RenderBreadcrumb
+                path={store.subflow_path.clone()}
+                on_navigate={on_breadcrumb_navigate}
+            />
+        }
+    };
+    let render_context_menu = match (store.context_menu.clone(), store.viewport.as_ref()) {
+        (Some(menu), Some(viewport)) => html! {
+            <RenderContextMenu
+                items={context_menu_items.for_target(&menu.target)}
+                {menu}
+                transform={transform.clone()}
+                dimensions={viewport.dimensions.clone()}
+                on_select={on_context_menu_item_select}
+                on_close={on_context_menu_close}
+            />
+        },
+        _ => html! {},
+    };
+    let render_quick_add = match (store.quick_add.clone(), store.viewport.as_ref()) {
+        (Some(quick_add), Some(viewport)) => html! {
+            <RenderQuickAdd
+                {quick_add}
+                templates={node_templates.clone()}
+                transform={transform.clone()}
+                dimensions={viewport.dimensions.clone()}
+                on_select={on_quick_add_select}
+                on_close={on_quick_add_close}
+            />
+        },
+        _ => html! {},
+    };
+
+    let highlight = store.graph.highlight(
+        *highlight_mode,
+        store.hovered_node.as_ref(),
+        &store.selection,
+    );
+
+    // the live region below announces it to screen readers
+    let render_notice = match store.notice.clone() {
+        Some(notice) => html! {
+            <div
+                aria-hidden="true"
+                class={classes!(
+                    "absolute",
+                    "bottom-2",
+                    "left-2",
+                    "px-2",
+                    "py-1",
+                    "rounded-sm",
+                    "text-sm",
+                    "text-red-300",
+                    "bg-black",
+                    "bg-opacity-70",
+                    "pointer-events-none",
+                )}
+            >
+                {notice}
+            </div>
+        },
+        None => html! {},
+    };
+
     html! {
         <div
             class={classes!(
@@ -170,6 +944,7 @@ pub fn workspace(
                 "flex-col",
             )}
         >
+            {render_breadcrumb}
             <div
                 ref={container_ref}
                 class={classes!(
@@ -184,19 +959,134 @@ pub fn workspace(
                     "border-neutral-400",
                     "border-2",
                     "relative",
+                    "overflow-hidden",
                 )}
-                onmousemove={on_container_mouse_move}
-                onmouseup={on_container_mouse_up}
+                tabindex="-1"
+                role="application"
+                aria-roledescription="flow editor"
+                // handle all touch gestures ourselves
+                style="touch-action: none;"
+                onpointerdown={on_container_pointer_down}
+                onpointermove={on_container_pointer_move}
+                onpointerup={on_container_pointer_up.clone()}
+                onpointercancel={on_container_pointer_up}
+                onwheel={on_container_wheel}
+                onkeydown={on_container_key_down}
+                oncopy={on_container_copy}
+                oncut={on_container_cut}
+                onpaste={on_container_paste}
+                oncontextmenu={on_container_context_menu}
+                ondragover={on_container_drag_over}
+                ondrop={on_container_drop}
             >
-                <RenderNodeList
-                    nodes={store.nodes.clone()}
-                    interaction_mode={store.interaction_mode.clone()}
-                    dispatcher={dispatcher.clone()}
-                />
-               <RenderEdgeList
-                    edges={store.edges.clone()}
-                />
+                // pan/zoom transformed pane
+                <div
+                    class={classes!(
+                        "absolute",
+                        "top-0",
+                        "left-0",
+                        "w-full",
+                        "h-full",
+                        "origin-top-left",
+                    )}
+                    style={format!("transform: {};", transform.to_css_string())}
+                >
+                    <RenderGroupList
+                        groups={store.groups.clone()}
+                        dispatcher={dispatcher.clone()}
+                    />
+                    <RenderCommentList
+                        comments={store.comments.clone()}
+                        selection={store.selection.clone()}
+                        dispatcher={dispatcher.clone()}
+                    />
+                    <RenderEdgeList
+                        edges={visible_edges}
+                        nodes={visible_nodes.clone()}
+                        interaction_mode={store.interaction_mode.clone()}
+                        highlight={highlight.clone()}
+                        dispatcher={dispatcher.clone()}
+                        on_edge_click={on_edge_click.clone()}
+                        on_edge_double_click={on_edge_double_click.clone()}
+                    />
+                    <RenderNodeList
+                        nodes={visible_nodes}
+                        edges={store.edges.clone()}
+                        graph={store.graph.clone()}
+                        interaction_mode={store.interaction_mode.clone()}
+                        selection={store.selection.clone()}
+                        {highlight}
+                        dispatcher={dispatcher.clone()}
+                    />
+                </div>
+                {render_context_menu}
+                {render_quick_add}
+                {render_notice}
+            </div>
+            // announces structural changes to screen readers
+            <div role="status" aria-live="polite" class={classes!("sr-only")}>
+                {store.announcement.clone()}
             </div>
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two overlapping nodes and an edge with a loose end.
+    fn broken_values() -> YewFlowValues {
+        let template = NodeTemplate {
+            kind: None,
+            title: "node".to_string(),
+            color: "#000000".to_string(),
+            inputs: 1,
+            outputs: 1,
+            fields: Vec::new(),
+        };
+        let (a, b) = (template.instantiate(0., 0.), template.instantiate(10., 0.));
+        YewFlowValues {
+            edges: vec![Edge {
+                from_output: Some(a.outputs[0].id.clone()),
+                ..Default::default()
+            }],
+            nodes: vec![a, b],
+            groups: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ignore_policy_loads_values_unchecked() {
+        let values = broken_values();
+        let (loaded, diagnostics) = check_values(&values, IntegrityPolicy::Ignore);
+        assert_eq!(loaded, Some(values));
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn repair_policy_loads_repaired_values() {
+        let values = broken_values();
+        let (loaded, diagnostics) = check_values(&values, IntegrityPolicy::Repair);
+        let loaded = loaded.unwrap();
+        assert_eq!(diagnostics, values.validate());
+        assert!(loaded.edges.is_empty());
+        assert_eq!(loaded.nodes, values.nodes);
+    }
+
+    #[test]
+    fn refuse_policy_refuses_only_errors() {
+        let mut values = broken_values();
+        let (loaded, diagnostics) = check_values(&values, IntegrityPolicy::Refuse);
+        assert_eq!(loaded, None);
+        assert_eq!(diagnostics, values.validate());
+
+        // overlaps alone are loaded
+        values.edges.clear();
+        let (loaded, diagnostics) = check_values(&values, IntegrityPolicy::Refuse);
+        assert_eq!(loaded, Some(values));
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!diagnostics[0].is_error());
+    }
+}
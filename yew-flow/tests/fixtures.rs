@@ -0,0 +1,71 @@
+//! Load the fixture of every format version in `fixtures/` and check that it
+//! upgrades to the current version without changing the flow.
+
+use std::{fs, path::Path};
+
+use serde_json::Value;
+use yew_flow::format::{document_version, FlowDocument, FORMAT_VERSION};
+
+fn read_fixture(version: u32) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(format!("v{}.json", version));
+    fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("missing fixture {}: {}", path.display(), err))
+}
+
+fn load_fixture(version: u32) -> FlowDocument {
+    FlowDocument::from_json_str(&read_fixture(version))
+        .unwrap_or_else(|err| panic!("v{} does not load: {}", version, err))
+}
+
+#[test]
+fn fixtures_have_their_version() {
+    for version in 0..=FORMAT_VERSION {
+        let value = serde_json::from_str::<Value>(&read_fixture(version)).unwrap();
+        assert_eq!(document_version(&value).unwrap(), version);
+    }
+}
+
+#[test]
+fn fixtures_upgrade_to_the_current_version() {
+    // compare as json, node references are never equal
+    let expected_flow = serde_json::to_value(&load_fixture(FORMAT_VERSION).flow).unwrap();
+    for version in 0..=FORMAT_VERSION {
+        let document = load_fixture(version);
+        assert_eq!(document.version, FORMAT_VERSION);
+        assert_eq!(
+            serde_json::to_value(&document.flow).unwrap(),
+            expected_flow,
+            "v{} changed the flow",
+            version
+        );
+    }
+}
+
+#[test]
+fn upgraded_fixtures_are_valid() {
+    for version in 0..=FORMAT_VERSION {
+        let document = load_fixture(version);
+        assert!(
+            !document.flow.validate().iter().any(|d| d.is_error()),
+            "v{} is invalid",
+            version
+        );
+    }
+}
+
+#[test]
+fn upgraded_fixtures_survive_a_round_trip() {
+    for version in 0..=FORMAT_VERSION {
+        let document = load_fixture(version);
+        let saved = document.to_json_string().unwrap();
+        let reloaded = FlowDocument::from_json_str(&saved).unwrap();
+        assert_eq!(
+            serde_json::to_value(&reloaded).unwrap(),
+            serde_json::to_value(&document).unwrap(),
+            "v{} does not survive a round trip",
+            version
+        );
+    }
+}